@@ -1,78 +1,104 @@
 use ckb_ssri_std::{public_module_traits::udt::UDTError, SSRIError};
 use ckb_std::error::SysError;
 use core::str::Utf8Error;
+use ssri_udt_sdk::{error_code, ArgError, MetadataError};
 
 /// Error
 #[repr(i8)]
 #[derive(Debug)]
 pub enum Error {
     // * CKB Error
-    IndexOutOfBound = 1,
-    ItemMissing = 2,
-    LengthNotEnough,
-    Encoding,
-    SpawnExceededMaxContentLength,
-    SpawnWrongMemoryLimit,
-    SpawnExceededMaxPeakMemory,
+    IndexOutOfBound = error_code::INDEX_OUT_OF_BOUND,
+    ItemMissing = error_code::ITEM_MISSING,
+    LengthNotEnough = error_code::LENGTH_NOT_ENOUGH,
+    Encoding = error_code::ENCODING,
+    SpawnExceededMaxContentLength = error_code::SPAWN_EXCEEDED_MAX_CONTENT_LENGTH,
+    SpawnWrongMemoryLimit = error_code::SPAWN_WRONG_MEMORY_LIMIT,
+    SpawnExceededMaxPeakMemory = error_code::SPAWN_EXCEEDED_MAX_PEAK_MEMORY,
 
     // * Rust Error
-    Utf8Error,
+    Utf8Error = error_code::UTF8_ERROR,
 
     // * SSRI Error
-    SSRIMethodsNotFound,
-    SSRIMethodsArgsInvalid,
-    SSRIMethodsNotImplemented,
-    SSRIMethodRequireHigherLevel,
-    InvalidVmVersion,
+    SSRIMethodsNotFound = error_code::SSRI_METHODS_NOT_FOUND,
+    SSRIMethodsArgsInvalid = error_code::SSRI_METHODS_ARGS_INVALID,
+    SSRIMethodsNotImplemented = error_code::SSRI_METHODS_NOT_IMPLEMENTED,
+    SSRIMethodRequireHigherLevel = error_code::SSRI_METHOD_REQUIRE_HIGHER_LEVEL,
+    InvalidVmVersion = error_code::INVALID_VM_VERSION,
 
     // * Molecule Error
-    MoleculeVerificationError,
+    MoleculeVerificationError = error_code::MOLECULE_VERIFICATION_ERROR,
 
     // * Serde Molecule Error
-    SerdeMoleculeErrorWithMessage,
+    SerdeMoleculeErrorWithMessage = error_code::SERDE_MOLECULE_ERROR_WITH_MESSAGE,
     /// Contains a general error message as a string.
     /// Occurs when the data length is incorrect while parsing a number or molecule header.
-    MismatchedLength,
+    MismatchedLength = error_code::MISMATCHED_LENGTH,
     /// Occurs when the data length is insufficient while parsing a number or molecule header.
-    SerdeMoleculeLengthNotEnough,
+    SerdeMoleculeLengthNotEnough = error_code::SERDE_MOLECULE_LENGTH_NOT_ENOUGH,
     /// Indicates that the method or type is not implemented. Not all types in Rust can be serialized.
-    Unimplemented,
+    Unimplemented = error_code::UNIMPLEMENTED,
     /// Occurs when assembling a molecule fixvec, and the size of each element is inconsistent.
-    AssembleFixvec,
+    AssembleFixvec = error_code::ASSEMBLE_FIXVEC,
     /// Occurs when the header or size is incorrect while parsing a molecule fixvec.
-    InvalidFixvec,
+    InvalidFixvec = error_code::INVALID_FIXVEC,
     /// Occurs when the field count is mismatched while parsing a molecule table.
-    MismatchedTableFieldCount,
+    MismatchedTableFieldCount = error_code::MISMATCHED_TABLE_FIELD_COUNT,
     /// Occurs when an overflow happens while parsing a molecule header.
-    Overflow,
+    Overflow = error_code::OVERFLOW,
     /// Indicates an error encountered while parsing a molecule array.
-    InvalidArray,
+    InvalidArray = error_code::INVALID_ARRAY,
     /// Indicates that non-fixed size fields are not allowed in a molecule struct, e.g., `Option`, `Vec`, `DynVec`, `enum`.
-    InvalidStructField,
+    InvalidStructField = error_code::INVALID_STRUCT_FIELD,
     /// Indicates that a map should have exactly two fields: a key and a value.
-    InvalidMap,
+    InvalidMap = error_code::INVALID_MAP,
     /// Indicates that the table header is invalid or malformed.
-    InvalidTable,
+    InvalidTable = error_code::INVALID_TABLE,
     /// Indicates that the table length is invalid or malformed.
-    InvalidTableLength,
+    InvalidTableLength = error_code::INVALID_TABLE_LENGTH,
     /// Indicates that the table header is invalid or malformed.
-    InvalidTableHeader,
+    InvalidTableHeader = error_code::INVALID_TABLE_HEADER,
     /// Indicates that the field count in serialization is mismatched.
-    InvalidTableCount,
+    InvalidTableCount = error_code::INVALID_TABLE_COUNT,
     /// Indicates that non-fixed size fields are not allowed in a molecule struct, e.g., `Option`, `Vec`, `DynVec`, `enum`.
-    MixTableAndStruct,
-    InvalidChar,
+    MixTableAndStruct = error_code::MIX_TABLE_AND_STRUCT,
+    InvalidChar = error_code::INVALID_CHAR,
 
     // * UDT Error
-    InsufficientBalance,
-    NoMintPermission,
-    NoBurnPermission,
+    InsufficientBalance = error_code::INSUFFICIENT_BALANCE,
+    NoMintPermission = error_code::NO_MINT_PERMISSION,
+    NoBurnPermission = error_code::NO_BURN_PERMISSION,
 
     // * SSRI Config Error
-    InvalidUDTArgs,
-    SSRIConfigNotFound,
-    SSRIConfigInvalidDataFormat,
-    InvalidTransactionInputs,
+    InvalidUDTArgs = error_code::INVALID_UDT_ARGS,
+    SSRIConfigNotFound = error_code::SSRI_CONFIG_NOT_FOUND,
+    SSRIConfigInvalidDataFormat = error_code::SSRI_CONFIG_INVALID_DATA_FORMAT,
+    InvalidTransactionInputs = error_code::INVALID_TRANSACTION_INPUTS,
+
+    // * SSRI Metadata Error
+    InvalidMetadataDecimals = error_code::INVALID_METADATA_DECIMALS,
+    EmptyMetadataName = error_code::EMPTY_METADATA_NAME,
+    MetadataNameTooLong = error_code::METADATA_NAME_TOO_LONG,
+    EmptyMetadataSymbol = error_code::EMPTY_METADATA_SYMBOL,
+    MetadataSymbolTooLong = error_code::METADATA_SYMBOL_TOO_LONG,
+    InvalidMetadataSymbolCharacter = error_code::INVALID_METADATA_SYMBOL_CHARACTER,
+    InvalidMetadataIconScheme = error_code::INVALID_METADATA_ICON_SCHEME,
+    MetadataIconTooLarge = error_code::METADATA_ICON_TOO_LARGE,
+    MetadataAlreadyMigrated = error_code::METADATA_ALREADY_MIGRATED,
+    MetadataDescriptionTooLong = error_code::METADATA_DESCRIPTION_TOO_LONG,
+    InvalidMetadataLink = error_code::INVALID_METADATA_LINK,
+    TooManyMetadataSocialLinks = error_code::TOO_MANY_METADATA_SOCIAL_LINKS,
+    MetadataCategoryTooLong = error_code::METADATA_CATEGORY_TOO_LONG,
+    MetadataExtraTooLarge = error_code::METADATA_EXTRA_TOO_LARGE,
+    MetadataExtraKeyNotFound = error_code::METADATA_EXTRA_KEY_NOT_FOUND,
+    AmbiguousMetadataIcon = error_code::AMBIGUOUS_METADATA_ICON,
+    MetadataIconNotFound = error_code::METADATA_ICON_NOT_FOUND,
+    MetadataIconHashMismatch = error_code::METADATA_ICON_HASH_MISMATCH,
+    InvalidMetadataLocale = error_code::INVALID_METADATA_LOCALE,
+    TooManyMetadataLocales = error_code::TOO_MANY_METADATA_LOCALES,
+    ImmutableMetadata = error_code::IMMUTABLE_METADATA,
+    ConflictingSSRIConfig = error_code::CONFLICTING_SSRI_CONFIG,
+    AmountOverflow = error_code::AMOUNT_OVERFLOW,
 }
 
 #[allow(non_snake_case, unused)]
@@ -144,3 +170,36 @@ impl From<UDTError> for Error {
         }
     }
 }
+
+impl From<MetadataError> for Error {
+    fn from(err: MetadataError) -> Self {
+        match err {
+            MetadataError::InvalidDecimals => Self::InvalidMetadataDecimals,
+            MetadataError::EmptyName => Self::EmptyMetadataName,
+            MetadataError::NameTooLong => Self::MetadataNameTooLong,
+            MetadataError::EmptySymbol => Self::EmptyMetadataSymbol,
+            MetadataError::SymbolTooLong => Self::MetadataSymbolTooLong,
+            MetadataError::InvalidSymbolCharacter => Self::InvalidMetadataSymbolCharacter,
+            MetadataError::InvalidIconScheme => Self::InvalidMetadataIconScheme,
+            MetadataError::IconTooLarge => Self::MetadataIconTooLarge,
+            MetadataError::DescriptionTooLong => Self::MetadataDescriptionTooLong,
+            MetadataError::InvalidLink => Self::InvalidMetadataLink,
+            MetadataError::TooManySocialLinks => Self::TooManyMetadataSocialLinks,
+            MetadataError::CategoryTooLong => Self::MetadataCategoryTooLong,
+            MetadataError::ExtraTooLarge => Self::MetadataExtraTooLarge,
+            MetadataError::AmbiguousIcon => Self::AmbiguousMetadataIcon,
+            MetadataError::InvalidLocale => Self::InvalidMetadataLocale,
+            MetadataError::TooManyLocales => Self::TooManyMetadataLocales,
+        }
+    }
+}
+
+impl From<ArgError> for Error {
+    fn from(err: ArgError) -> Self {
+        match err {
+            ArgError::Invalid => Self::SSRIMethodsArgsInvalid,
+            ArgError::Encoding => Self::Encoding,
+            ArgError::Molecule => Self::MoleculeVerificationError,
+        }
+    }
+}
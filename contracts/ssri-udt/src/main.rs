@@ -5,18 +5,18 @@
 #[cfg(test)]
 extern crate alloc;
 
-use alloc::borrow::Cow;
+use alloc::{borrow::Cow, vec::Vec};
 use ckb_ssri_std::{public_module_traits::udt::UDT, utils::should_fallback};
 use ckb_ssri_std_proc_macro::ssri_methods;
 use ckb_std::{
-    ckb_types::{
-        packed::{Script, Transaction},
-        prelude::*,
-    },
+    ckb_types::prelude::*,
     debug,
     syscalls::{pipe, write},
 };
-use serde_molecule::from_slice;
+use ssri_udt_sdk::decode::{
+    self, arg, decode_flag, decode_metadata, decode_optional_tx, decode_recipients, decode_script,
+    decode_tx, decode_u32,
+};
 
 #[cfg(not(test))]
 use ckb_std::default_alloc;
@@ -27,16 +27,17 @@ ckb_std::entry!(program_entry);
 #[cfg(not(test))]
 default_alloc!();
 
-mod config;
 mod error;
 mod fallback;
 mod modules;
 mod utils;
 
 use error::Error;
+use modules::SSRIMetadataExt;
 
 fn program_entry_wrap() -> Result<(), Error> {
     let argv = ckb_std::env::argv();
+    let args: Vec<&[u8]> = argv.iter().map(|arg| arg.to_bytes()).collect();
 
     if should_fallback()? {
         return Ok(fallback::fallback()?);
@@ -55,78 +56,80 @@ fn program_entry_wrap() -> Result<(), Error> {
         "UDT.icon" => Ok(Cow::from(modules::SSRIUDT::icon()?.to_vec())),
         "UDT.transfer" => {
             debug!("program_entry_wrap | Entered UDT.transfer");
-            let to_lock_vec_molecule: modules::ScriptLikeVec = from_slice(decode_hex(argv[2].as_ref())?.as_slice(), false).map_err(|_|Error::MoleculeVerificationError)?;
-            let to_lock_vec = to_lock_vec_molecule.scripts.into_iter().map(|script|
-                Script::new_builder()
-                    .code_hash(script.code_hash.pack())
-                    .hash_type(script.hash_type.into())
-                    .args(script.args.pack())
-                    .build()
-            ).collect::<Vec<Script>>();
-
-            let to_amount_bytes = decode_hex(argv[3].as_ref())?;
-            let to_amount_vec: Vec<u128> = to_amount_bytes[4..]
-                .chunks(16)
-                .map(|chunk| {
-                    return u128::from_le_bytes(chunk.try_into().unwrap())}
-                )
-                .collect();
-
-            if argv[2].is_empty() || argv[3].is_empty() || to_lock_vec.len() != to_amount_vec.len() {
-                Err(Error::SSRIMethodsArgsInvalid)?;
-            }
-
-            let tx: Option<Transaction> = if argv[1].as_ref().is_empty() {
-                None
-            } else {
-                Some(Transaction::from_compatible_slice(&decode_hex(argv[1].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?)
-            };
-
+            let (to_lock_vec, to_amount_vec) = decode_recipients(arg(&args, 2)?, arg(&args, 3)?)?;
+            debug!("program_entry_wrap | to_lock_vec: {:?}", to_lock_vec);
+            debug!("program_entry_wrap | to_amount_vec: {:?}", to_amount_vec);
+            let tx = decode_optional_tx(arg(&args, 1)?)?;
             Ok(Cow::from(modules::SSRIUDT::transfer(tx, to_lock_vec, to_amount_vec)?.as_bytes().to_vec()))
         },
         "UDT.mint" => {
             debug!("program_entry_wrap | Entered UDT.mint");
-            let to_lock_vec_molecule: modules::ScriptLikeVec = from_slice(decode_hex(argv[2].as_ref())?.as_slice(), false).map_err(|_|Error::MoleculeVerificationError)?;
-            let to_lock_vec = to_lock_vec_molecule.scripts.into_iter().map(|script|
-                Script::new_builder()
-                    .code_hash(script.code_hash.pack())
-                    .hash_type(script.hash_type.into())
-                    .args(script.args.pack())
-                    .build()
-            ).collect::<Vec<Script>>();
+            let (to_lock_vec, to_amount_vec) = decode_recipients(arg(&args, 2)?, arg(&args, 3)?)?;
             debug!("program_entry_wrap | to_lock_vec: {:?}", to_lock_vec);
-
-            let to_amount_bytes = decode_hex(argv[3].as_ref())?;
-            let to_amount_vec: Vec<u128> = to_amount_bytes[4..]
-                .chunks(16)
-                .map(|chunk| {
-                    return u128::from_le_bytes(chunk.try_into().unwrap())}
-                )
-                .collect();
             debug!("program_entry_wrap | to_amount_vec: {:?}", to_amount_vec);
-
-            if argv[2].is_empty() || argv[3].is_empty() || to_lock_vec.len() != to_amount_vec.len() {
-                Err(Error::SSRIMethodsArgsInvalid)?;
-            }
-
-            let tx: Option<Transaction> = if argv[1].as_ref().is_empty() {
-                None
-            } else {
-                Some(Transaction::from_compatible_slice(&decode_hex(argv[1].as_ref())?).map_err(|_|Error::MoleculeVerificationError)?)
-            };
-
+            let tx = decode_optional_tx(arg(&args, 1)?)?;
             Ok(Cow::from(modules::SSRIUDT::mint(tx, to_lock_vec, to_amount_vec)?.as_bytes().to_vec()))
         },
         "SSRIUDT.create" => {
             debug!("program_entry_wrap | Entered SSRIUDT.create");
-            let tx_bytes = decode_hex(argv.get(1).ok_or(Error::SSRIMethodsArgsInvalid)?)?;
-            let owner_lock_bytes = decode_hex(argv.get(2).ok_or(Error::SSRIMethodsArgsInvalid)?)?;
-            let ssri_metadata_bytes = decode_hex(argv.get(3).ok_or(Error::SSRIMethodsArgsInvalid)?)?;
-
-            let tx = Transaction::from_compatible_slice(&tx_bytes).map_err(|_|Error::MoleculeVerificationError)?;
-            let owner_lock = Script::from_compatible_slice(&owner_lock_bytes).map_err(|_|Error::MoleculeVerificationError)?;
-            let ssri_metadata: modules::SSRIMetadata = from_slice(&ssri_metadata_bytes, false).map_err(|_|Error::MoleculeVerificationError)?;
-            Ok(Cow::from(ssri_metadata.generate_ssri_create_tx(tx, owner_lock)?.as_bytes().to_vec()))
+            let tx = decode_tx(arg(&args, 1)?)?;
+            let owner_lock = decode_script(arg(&args, 2)?)?;
+            let ssri_metadata = decode_metadata(arg(&args, 3)?)?;
+            let immutable = decode_flag(arg(&args, 4).ok())?;
+            Ok(Cow::from(ssri_metadata.generate_ssri_create_tx(tx, owner_lock, immutable)?.as_bytes().to_vec()))
+        },
+        "SSRIUDT.metadata" => Ok(Cow::from(modules::SSRIUDT::metadata()?.encode()?)),
+        "SSRIUDT.description" => Ok(Cow::from(modules::SSRIUDT::description()?.to_vec())),
+        "SSRIUDT.website" => Ok(Cow::from(modules::SSRIUDT::website()?.to_vec())),
+        "SSRIUDT.social_links" => Ok(Cow::from(modules::SSRIUDT::social_links()?.as_bytes().to_vec())),
+        "SSRIUDT.category" => Ok(Cow::from(modules::SSRIUDT::category()?.to_vec())),
+        "SSRIUDT.extra" => {
+            debug!("program_entry_wrap | Entered SSRIUDT.extra");
+            let key = decode::decode_hex(arg(&args, 1)?)?;
+            Ok(Cow::from(modules::SSRIUDT::extra(&key)?.to_vec()))
+        },
+        "SSRIUDT.name_localized" => {
+            debug!("program_entry_wrap | Entered SSRIUDT.name_localized");
+            let locale = decode::decode_hex(arg(&args, 1)?)?;
+            Ok(Cow::from(modules::SSRIUDT::name_localized(&locale)?.to_vec()))
+        },
+        "SSRIUDT.description_localized" => {
+            debug!("program_entry_wrap | Entered SSRIUDT.description_localized");
+            let locale = decode::decode_hex(arg(&args, 1)?)?;
+            Ok(Cow::from(modules::SSRIUDT::description_localized(&locale)?.to_vec()))
+        },
+        "SSRIUDT.migrate_metadata" => {
+            debug!("program_entry_wrap | Entered SSRIUDT.migrate_metadata");
+            let tx = decode_optional_tx(arg(&args, 1).unwrap_or_default())?;
+            Ok(Cow::from(modules::SSRIUDT::migrate_metadata(tx)?.as_bytes().to_vec()))
+        },
+        "SSRIUDT.create_and_mint" => {
+            debug!("program_entry_wrap | Entered SSRIUDT.create_and_mint");
+            let tx = decode_tx(arg(&args, 1)?)?;
+            let owner_lock = decode_script(arg(&args, 2)?)?;
+            let ssri_metadata = decode_metadata(arg(&args, 3)?)?;
+            let (to_lock_vec, to_amount_vec) = decode_recipients(arg(&args, 4)?, arg(&args, 5)?)?;
+            Ok(Cow::from(modules::SSRIUDT::create_and_mint(tx, owner_lock, ssri_metadata, to_lock_vec, to_amount_vec)?.as_bytes().to_vec()))
+        },
+        "SSRIUDT.created_scripts" => {
+            debug!("program_entry_wrap | Entered SSRIUDT.created_scripts");
+            let tx = decode_tx(arg(&args, 1)?)?;
+            Ok(Cow::from(modules::SSRIUDT::created_scripts(tx)?.encode()?))
+        },
+        "SSRIUDT.airdrop" => {
+            debug!("program_entry_wrap | Entered SSRIUDT.airdrop");
+            let (to_lock_vec, to_amount_vec) = decode_recipients(arg(&args, 2)?, arg(&args, 3)?)?;
+            let max_outputs = decode_u32(arg(&args, 4)?)?;
+            let tx = decode_optional_tx(arg(&args, 1)?)?;
+            Ok(Cow::from(modules::SSRIUDT::airdrop(tx, to_lock_vec, to_amount_vec, max_outputs)?.as_bytes().to_vec()))
+        },
+        "SSRIUDT.describe_transaction" => {
+            debug!("program_entry_wrap | Entered SSRIUDT.describe_transaction");
+            Ok(Cow::from(modules::SSRIUDT::describe_transaction()?.encode()?))
+        },
+        "SSRIUDT.verify" => {
+            debug!("program_entry_wrap | Entered SSRIUDT.verify");
+            Ok(Cow::from(modules::SSRIUDT::verify()?.encode()?))
         },
     )?;
     let pipe = pipe()?;
@@ -1,98 +1,110 @@
-use alloc::{string::String, vec::Vec};
-use ckb_hash::new_blake2b;
-use ckb_ssri_std::{
-    public_module_traits::udt::{ScriptLike, UDT},
-    utils::high_level::{find_cell_data_by_out_point, find_out_point_by_type},
+use alloc::{
+    collections::{btree_map::Entry, BTreeMap},
+    format,
+    vec::Vec,
 };
+use ckb_ssri_std::{public_module_traits::udt::UDT, utils::high_level::find_out_point_by_type};
 use ckb_std::{
     ckb_constants::Source,
     ckb_types::{
         bytes::Bytes,
-        core::ScriptHashType,
         packed::{
-            Byte32Vec, BytesVec, BytesVecBuilder, CellDep, CellDepVecBuilder, CellInputVec,
-            CellOutputBuilder, CellOutputVecBuilder, OutPoint, RawTransactionBuilder, Script,
-            ScriptBuilder, ScriptOptBuilder, Transaction, TransactionBuilder, Uint32, Uint64,
+            BytesVec, CellDep, CellInput, CellOutputBuilder, OutPoint, RawTransaction, Script,
+            Transaction, TransactionVec,
         },
         prelude::*,
     },
     debug,
-    high_level::load_script,
+    high_level::{load_script, load_tx_hash},
+};
+use core::cmp::Ordering;
+use ssri_udt_sdk::{
+    metadata::CURRENT_METADATA_VERSION,
+    type_id::{calc_type_id, find_created_type_id_script, type_id_script},
+    UDTArgs,
 };
-use serde::{Deserialize, Serialize};
-use serde_molecule::{dynvec_serde, from_slice, to_vec};
 
 use crate::{
-    config::TYPE_ID_SCRIPT_CODE_HASH,
     error::Error,
+    fallback::fallback,
     utils::{
-        check_owner_mode, collect_inputs_amount, collect_outputs_amount, find_ssri_config_cell,
+        check_owner_mode, collect_amounts_by_lock, collect_inputs_amount, collect_outputs_amount,
+        find_cell_by_out_point, find_cell_data_by_out_point, find_committed_metadata,
+        occupied_capacity, resolve_owner_lock_hash, resolve_ssri_config_cell, udt_args,
     },
 };
 
-#[derive(Serialize, Deserialize)]
-pub struct SSRIMetadata {
-    pub name: String,
-    pub symbol: String,
-    pub decimals: u8,
-    pub icon: String,
+pub use ssri_udt_sdk::{
+    CreatedScripts, IconRef, LockDelta, MetadataRecord, SSRIMetadata, TransactionReport,
+    VerificationPath, VerifyResult,
+};
+
+/// On-chain lookups and transaction generation for the `SSRIMetadata` cell.
+pub trait SSRIMetadataExt: Sized {
+    fn new_from_onchain_search() -> Result<Self, Error>;
+    fn search_outpoint() -> Result<OutPoint, Error>;
+    fn generate_ssri_create_tx(
+        &self,
+        tx: Transaction,
+        owner_lock: Script,
+        immutable: bool,
+    ) -> Result<Transaction, Error>;
 }
 
-impl SSRIMetadata {
-    pub fn new_from_onchain_search() -> Result<Self, Error> {
-        let ssri_config_outpoint = Self::search_outpoint()?;
-        let ssri_config = find_cell_data_by_out_point(ssri_config_outpoint)?;
-        Ok(from_slice(&ssri_config, false)?)
+impl SSRIMetadataExt for SSRIMetadata {
+    // Type ID and singleton args need `script` level, immutable args
+    // `transaction` level with the metadata cell as a cell dep.
+    fn new_from_onchain_search() -> Result<Self, Error> {
+        let args = udt_args()?;
+        let ssri_config = match args {
+            UDTArgs::Immutable { .. } => {
+                load_tx_hash().map_err(|_| Error::SSRIMethodRequireHigherLevel)?;
+                find_committed_metadata(&args, Source::CellDep)?.ok_or(Error::SSRIConfigNotFound)?
+            }
+            _ => find_cell_data_by_out_point(&Self::search_outpoint()?)?,
+        };
+        Ok(SSRIMetadata::decode(&ssri_config)?)
     }
 
     // must run at `script` level
-    pub fn search_outpoint() -> Result<OutPoint, Error> {
-        let type_id_args: Vec<u8> = load_script()?.args().unpack();
-        let ssri_config_type_script = Script::new_builder()
-            .code_hash(TYPE_ID_SCRIPT_CODE_HASH.pack())
-            .hash_type(ScriptHashType::Type.into())
-            .args(type_id_args.pack())
-            .build();
-        let ssri_config_outpoint = find_out_point_by_type(ssri_config_type_script)?;
+    fn search_outpoint() -> Result<OutPoint, Error> {
+        let Some(ssri_type_script) = udt_args()?.metadata_type_script() else {
+            return Err(Error::ImmutableMetadata);
+        };
+        let ssri_config_outpoint = find_out_point_by_type(ssri_type_script)?;
         Ok(ssri_config_outpoint)
     }
 
-    pub fn generate_ssri_create_tx(
+    /// Appends the metadata cell, locked by `owner_lock`. With `immutable` it
+    /// has no type script; the UDT args commit to its data instead, see
+    /// `UDTArgs::immutable`.
+    fn generate_ssri_create_tx(
         &self,
         tx: Transaction,
         owner_lock: Script,
+        immutable: bool,
     ) -> Result<Transaction, Error> {
+        self.validate()?;
         let Some(first_input_cell_input) = tx.raw().inputs().get(0) else {
             return Err(Error::InvalidTransactionInputs);
         };
-        let type_id_args: [u8; 32] = {
-            let mut hasher = new_blake2b();
-            hasher.update(first_input_cell_input.as_slice());
-            hasher.update(&(tx.raw().outputs().len() as u32).to_le_bytes());
-            let mut ret = [0; 32];
-            hasher.finalize(&mut ret);
-            ret
+        let type_script = if immutable {
+            None
+        } else {
+            let type_id_args =
+                calc_type_id(&first_input_cell_input, tx.raw().outputs().len() as u64);
+            Some(type_id_script(&type_id_args))
         };
         let tx_builder = tx.raw().as_builder();
         let mut outputs_vec_builder = tx.raw().outputs().as_builder();
         let mut outputs_data_vec_builder = tx.raw().outputs_data().as_builder();
         outputs_vec_builder = outputs_vec_builder.push(
-            // type_id script
             CellOutputBuilder::default()
                 .lock(owner_lock)
-                .type_(
-                    Some(
-                        ScriptBuilder::default()
-                            .code_hash(TYPE_ID_SCRIPT_CODE_HASH.pack())
-                            .hash_type(ScriptHashType::Type.into())
-                            .args(type_id_args.to_vec().pack())
-                            .build(),
-                    )
-                    .pack(),
-                )
+                .type_(type_script.pack())
                 .build(),
         );
-        outputs_data_vec_builder = outputs_data_vec_builder.push(to_vec(&self, false)?.pack());
+        outputs_data_vec_builder = outputs_data_vec_builder.push(self.encode()?.pack());
         Ok(tx
             .as_builder()
             .raw(
@@ -105,106 +117,467 @@ impl SSRIMetadata {
     }
 }
 
-#[derive(Serialize, Deserialize)]
-#[serde(transparent)]
-pub struct ScriptLikeVec {
-    #[serde(with = "dynvec_serde")]
-    pub scripts: Vec<ScriptLike>,
-}
-
 pub struct SSRIUDT;
 
-// #[ssri_module]
-impl UDT for SSRIUDT {
-    type Error = Error;
+impl SSRIUDT {
+    // #[ssri_method(level = "script")]
+    /// Returns the metadata Type ID script created by `tx`, as produced by
+    /// `SSRIUDT.create`, and the ssri-udt type script referencing it. The code
+    /// hash and hash type come from the running script; its args are ignored.
+    /// An immutable metadata cell has no Type ID and fails with
+    /// `SSRIConfigNotFound`; its args come from `UDTArgs::immutable` instead.
+    pub fn created_scripts(tx: Transaction) -> Result<CreatedScripts, Error> {
+        debug!("Entered SSRIUDT::created_scripts");
+        if tx.raw().inputs().is_empty() {
+            return Err(Error::InvalidTransactionInputs);
+        }
+        let metadata_type_script =
+            find_created_type_id_script(&tx).ok_or(Error::SSRIConfigNotFound)?;
+        let udt_type_script = load_script()?
+            .as_builder()
+            .args(metadata_type_script.args())
+            .build();
+        Ok(CreatedScripts::new(&metadata_type_script, &udt_type_script))
+    }
 
-    // #[ssri_method(level = "script", transaction = true)]
-    fn transfer(
+    /// Appends one output of type `udt_type` per recipient to `tx`, and
+    /// `cell_dep` if any. Every other field of `tx` is kept as it is.
+    fn append_udt_outputs(
         tx: Option<Transaction>,
-        to_lock_vec: Vec<Script>,
-        to_amount_vec: Vec<u128>,
+        udt_type: Script,
+        to_lock_vec: &[Script],
+        to_amount_vec: &[u128],
+        cell_dep: Option<CellDep>,
     ) -> Result<Transaction, Error> {
-        debug!("Entered UDT::transfer");
         if to_amount_vec.len() != to_lock_vec.len() {
             return Err(Error::SSRIMethodsArgsInvalid);
         }
-        let tx_builder = match tx {
-            Some(ref tx) => tx.clone().as_builder(),
-            None => TransactionBuilder::default(),
+        let udt_type = Some(udt_type).pack();
+        let tx = tx.unwrap_or_default();
+        let raw = tx.raw();
+        let outputs = raw
+            .outputs()
+            .as_builder()
+            .extend(to_lock_vec.iter().map(|to_lock| {
+                CellOutputBuilder::default()
+                    .lock(to_lock.clone())
+                    .type_(udt_type.clone())
+                    .build()
+            }))
+            .build();
+        let outputs_data = raw
+            .outputs_data()
+            .as_builder()
+            .extend(
+                to_amount_vec
+                    .iter()
+                    .map(|to_amount| to_amount.pack().as_bytes().pack()),
+            )
+            .build();
+        let cell_deps = raw.cell_deps().as_builder().extend(cell_dep).build();
+        Ok(tx
+            .as_builder()
+            .raw(
+                raw.as_builder()
+                    .outputs(outputs)
+                    .outputs_data(outputs_data)
+                    .cell_deps(cell_deps)
+                    .build(),
+            )
+            .build())
+    }
+
+    /// The metadata cell dep a mint adds: the cell found by the singleton
+    /// type script of the args. Immutable args have no cell to look up, so
+    /// nothing is added and the caller supplies the cell dep holding the
+    /// committed data.
+    fn metadata_cell_dep() -> Result<Option<CellDep>, Error> {
+        if udt_args()?.metadata_type_script().is_none() {
+            return Ok(None);
+        }
+        let out_point = SSRIMetadata::search_outpoint()?;
+        Ok(Some(CellDep::new_builder().out_point(out_point).build()))
+    }
+
+    /// `UDT::verify_transfer` on group sums the caller already has, so
+    /// `fallback` reads each cell once.
+    pub fn verify_transfer_amounts(inputs_amount: u128, outputs_amount: u128) -> Result<(), Error> {
+        if inputs_amount < outputs_amount {
+            return Err(Error::InsufficientBalance);
+        }
+        debug!("inputs_amount: {}", inputs_amount);
+        debug!("outputs_amount: {}", outputs_amount);
+        Ok(())
+    }
+
+    // #[ssri_method(level = "transaction")]
+    /// Reports the UDT effects of the transaction and the path `fallback`
+    /// would take, without verifying it.
+    pub fn describe_transaction() -> Result<TransactionReport, Error> {
+        debug!("Entered SSRIUDT::describe_transaction");
+        load_tx_hash().map_err(|_| Error::SSRIMethodRequireHigherLevel)?;
+
+        let mut amounts: BTreeMap<[u8; 32], (u128, u128)> = BTreeMap::new();
+        for (lock_hash, amount) in collect_amounts_by_lock(Source::GroupInput)? {
+            amounts.entry(lock_hash).or_default().0 = amount;
+        }
+        for (lock_hash, amount) in collect_amounts_by_lock(Source::GroupOutput)? {
+            amounts.entry(lock_hash).or_default().1 = amount;
+        }
+        let lock_deltas = amounts
+            .into_iter()
+            .map(|(lock_hash, (input_amount, output_amount))| LockDelta {
+                lock_hash,
+                input_amount,
+                output_amount,
+            })
+            .collect();
+
+        let input_amount = collect_inputs_amount()?;
+        let output_amount = collect_outputs_amount()?;
+        // Only the owner lookup decides; whether the metadata is valid is left
+        // to `verify`. Without a metadata cell there is no owner to find.
+        let owner_mode = match resolve_owner_lock_hash(&udt_args()?) {
+            Ok(owner_lock_hash) => check_owner_mode(&owner_lock_hash)?,
+            Err(Error::SSRIConfigNotFound) => false,
+            Err(err) => return Err(err),
         };
-        let raw_tx_builder = match tx {
-            Some(ref tx) => tx.clone().raw().as_builder(),
-            None => RawTransactionBuilder::default(),
+        let path = match input_amount.cmp(&output_amount) {
+            Ordering::Less => VerificationPath::Mint,
+            Ordering::Equal => VerificationPath::Transfer,
+            Ordering::Greater => VerificationPath::Rejected,
         };
+        Ok(TransactionReport {
+            lock_deltas,
+            input_amount,
+            output_amount,
+            minted: output_amount.saturating_sub(input_amount),
+            burned: input_amount.saturating_sub(output_amount),
+            transferred: input_amount.min(output_amount),
+            owner_mode,
+            path: path as u8,
+        })
+    }
 
-        let mut cell_output_vec_builder = match tx {
-            Some(ref tx) => tx.clone().raw().outputs().as_builder(),
-            None => CellOutputVecBuilder::default(),
-        };
+    // #[ssri_method(level = "script")]
+    /// Returns the whole metadata with the out point and lock of its cell,
+    /// so explorers need one call instead of one per getter. Immutable args
+    /// have no single cell to report and fail with `ImmutableMetadata`.
+    pub fn metadata() -> Result<MetadataRecord, Error> {
+        debug!("Entered SSRIUDT::metadata");
+        let out_point = SSRIMetadata::search_outpoint()?;
+        let cell = find_cell_by_out_point(&out_point)?;
+        let metadata = SSRIMetadata::decode(&find_cell_data_by_out_point(&out_point)?)?;
+        Ok(MetadataRecord::new(metadata, &out_point, &cell.lock()))
+    }
 
-        for to_lock in to_lock_vec.iter() {
-            let new_transfer_output = CellOutputBuilder::default()
-                .type_(
-                    ScriptOptBuilder::default()
-                        .set(Some(load_script()?))
-                        .build(),
-                )
-                .capacity(Uint64::default())
-                .lock(to_lock.clone())
-                .build();
-            cell_output_vec_builder = cell_output_vec_builder.push(new_transfer_output);
+    // #[ssri_method(level = "script")]
+    pub fn description() -> Result<Bytes, Error> {
+        let ssri_metadata =
+            SSRIMetadata::new_from_onchain_search().map_err(|_| Error::SSRIConfigNotFound)?;
+        Ok(ssri_metadata.description.into_bytes().into())
+    }
+
+    // #[ssri_method(level = "script")]
+    /// `UDT.name` in `locale`, or in its primary language, falling back to the
+    /// default name.
+    pub fn name_localized(locale: &[u8]) -> Result<Bytes, Error> {
+        if locale.is_empty() {
+            return Self::name();
         }
+        let locale = core::str::from_utf8(locale)?;
+        let ssri_metadata =
+            SSRIMetadata::new_from_onchain_search().map_err(|_| Error::SSRIConfigNotFound)?;
+        Ok(Bytes::copy_from_slice(
+            ssri_metadata.name_in(locale).as_bytes(),
+        ))
+    }
 
-        let mut outputs_data_builder = match tx {
-            Some(ref tx) => tx.clone().raw().outputs_data().as_builder(),
-            None => BytesVecBuilder::default(),
-        };
+    // #[ssri_method(level = "script")]
+    /// `SSRIUDT.description` in `locale`, with the same fallback as
+    /// `name_localized`.
+    pub fn description_localized(locale: &[u8]) -> Result<Bytes, Error> {
+        let locale = core::str::from_utf8(locale)?;
+        let ssri_metadata =
+            SSRIMetadata::new_from_onchain_search().map_err(|_| Error::SSRIConfigNotFound)?;
+        Ok(Bytes::copy_from_slice(
+            ssri_metadata.description_in(locale).as_bytes(),
+        ))
+    }
+
+    // #[ssri_method(level = "script")]
+    pub fn website() -> Result<Bytes, Error> {
+        let ssri_metadata =
+            SSRIMetadata::new_from_onchain_search().map_err(|_| Error::SSRIConfigNotFound)?;
+        Ok(ssri_metadata.website.into_bytes().into())
+    }
+
+    // #[ssri_method(level = "script")]
+    /// Returns the social links as a molecule `BytesVec`.
+    pub fn social_links() -> Result<BytesVec, Error> {
+        let ssri_metadata =
+            SSRIMetadata::new_from_onchain_search().map_err(|_| Error::SSRIConfigNotFound)?;
+        Ok(BytesVec::new_builder()
+            .extend(
+                ssri_metadata
+                    .social_links
+                    .into_iter()
+                    .map(|link| link.into_bytes().pack()),
+            )
+            .build())
+    }
+
+    // #[ssri_method(level = "script")]
+    pub fn category() -> Result<Bytes, Error> {
+        let ssri_metadata =
+            SSRIMetadata::new_from_onchain_search().map_err(|_| Error::SSRIConfigNotFound)?;
+        Ok(ssri_metadata.category.into_bytes().into())
+    }
+
+    // #[ssri_method(level = "script")]
+    /// Returns the raw value stored under `key` in the metadata extension map.
+    pub fn extra(key: &[u8]) -> Result<Bytes, Error> {
+        let mut ssri_metadata =
+            SSRIMetadata::new_from_onchain_search().map_err(|_| Error::SSRIConfigNotFound)?;
+        let key = core::str::from_utf8(key)?;
+        ssri_metadata
+            .extra
+            .remove(key)
+            .map(Into::into)
+            .ok_or(Error::MetadataExtraKeyNotFound)
+    }
 
-        for to_amount in to_amount_vec.iter() {
-            outputs_data_builder = outputs_data_builder.push(to_amount.pack().as_bytes().pack());
+    // #[ssri_method(level = "script", transaction = true)]
+    /// Rewrites the metadata cell of this UDT in the current layout: spends it
+    /// and recreates it with the same lock and type. The capacity is kept, or
+    /// raised to what the new layout occupies. The caller adds the owner's
+    /// witness and an input covering the difference. Metadata breaking the
+    /// rules of the current layout is refused with that rule's error; the
+    /// owner repairs it by rewriting the cell with corrected metadata instead.
+    pub fn migrate_metadata(tx: Option<Transaction>) -> Result<Transaction, Error> {
+        debug!("Entered SSRIUDT::migrate_metadata");
+        let out_point = SSRIMetadata::search_outpoint()?;
+        let cell = find_cell_by_out_point(&out_point)?;
+        let metadata = SSRIMetadata::decode(&find_cell_data_by_out_point(&out_point)?)
+            .map_err(|_| Error::SSRIConfigInvalidDataFormat)?;
+        if metadata.version >= CURRENT_METADATA_VERSION {
+            return Err(Error::MetadataAlreadyMigrated);
         }
+        metadata.validate()?;
+        let data = metadata.encode()?;
+        let capacity = u64::max(
+            cell.capacity().unpack(),
+            occupied_capacity(&cell, data.len()),
+        );
+        let cell = cell.as_builder().capacity(capacity.pack()).build();
 
-        Ok(tx_builder
+        let tx = tx.unwrap_or_default();
+        let raw = tx.raw();
+        Ok(tx
+            .clone()
+            .as_builder()
             .raw(
-                raw_tx_builder
-                    .version(
-                        tx.clone()
-                            .map(|t| t.raw().version())
-                            .unwrap_or_else(|| Uint32::default()),
-                    )
-                    .header_deps(
-                        tx.clone()
-                            .map(|t| t.raw().header_deps())
-                            .unwrap_or_else(|| Byte32Vec::default()),
-                    )
+                raw.clone()
+                    .as_builder()
                     .inputs(
-                        tx.clone()
-                            .map(|t| t.raw().inputs())
-                            .unwrap_or_else(|| CellInputVec::default()),
+                        raw.inputs()
+                            .as_builder()
+                            .push(CellInput::new(out_point, 0))
+                            .build(),
                     )
-                    .outputs(cell_output_vec_builder.build())
-                    .outputs_data(outputs_data_builder.build())
+                    .outputs(raw.outputs().as_builder().push(cell).build())
+                    .outputs_data(raw.outputs_data().as_builder().push(data.pack()).build())
                     .build(),
             )
-            .witnesses(
-                tx.clone()
-                    .map(|t| t.witnesses())
-                    .unwrap_or_else(|| BytesVec::default()),
-            )
             .build())
     }
 
-    fn verify_transfer() -> Result<(), Self::Error> {
-        debug!("Entered UDT::verify_transfer");
-        let inputs_amount = collect_inputs_amount()?;
-        let outputs_amount = collect_outputs_amount()?;
+    // #[ssri_method(level = "script", transaction = true)]
+    /// Creates the metadata cell like `SSRIUDT.create` and mints the initial
+    /// supply against it in the same transaction. `verify_mint` finds the new
+    /// metadata cell among the outputs, so an input must still carry
+    /// `owner_lock`. The UDT type script takes its code hash and hash type from
+    /// the running script and its args from the new Type ID.
+    pub fn create_and_mint(
+        tx: Transaction,
+        owner_lock: Script,
+        metadata: SSRIMetadata,
+        to_lock_vec: Vec<Script>,
+        to_amount_vec: Vec<u128>,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered SSRIUDT::create_and_mint");
+        if to_lock_vec.len() != to_amount_vec.len() {
+            return Err(Error::SSRIMethodsArgsInvalid);
+        }
+        let tx = metadata.generate_ssri_create_tx(tx, owner_lock, false)?;
+        let metadata_type_script =
+            find_created_type_id_script(&tx).ok_or(Error::SSRIConfigNotFound)?;
+        let udt_type_script = load_script()?
+            .as_builder()
+            .args(metadata_type_script.args())
+            .build();
+        Self::append_udt_outputs(
+            Some(tx),
+            udt_type_script,
+            &to_lock_vec,
+            &to_amount_vec,
+            None,
+        )
+    }
 
-        if inputs_amount < outputs_amount {
-            return Err(Error::InsufficientBalance);
+    // #[ssri_method(level = "script", transaction = true)]
+    /// Mints to many recipients at once. Amounts for the same lock are merged
+    /// into one output, in order of first appearance, and at most
+    /// `max_outputs` UDT outputs are added per transaction.
+    ///
+    /// The first batch goes into `tx`, which needs an owner lock input like
+    /// `mint`. Every batch but the last also ends with an empty output locked
+    /// by the owner, for the next batch to spend for its owner mode. Further
+    /// batches keep the version, cell deps and header deps of `tx`, but none
+    /// of its inputs or outputs. The hash of a batch is only known once the
+    /// caller completes it, so the caller adds that input to the next batch.
+    pub fn airdrop(
+        tx: Option<Transaction>,
+        to_lock_vec: Vec<Script>,
+        to_amount_vec: Vec<u128>,
+        max_outputs: u32,
+    ) -> Result<TransactionVec, Error> {
+        debug!("Entered SSRIUDT::airdrop");
+        if to_lock_vec.len() != to_amount_vec.len() || max_outputs == 0 {
+            return Err(Error::SSRIMethodsArgsInvalid);
         }
-        debug!("inputs_amount: {}", inputs_amount);
-        debug!("outputs_amount: {}", outputs_amount);
-        Ok(())
+
+        let mut recipients: Vec<(Script, u128)> = Vec::new();
+        let mut recipient_indices: BTreeMap<Vec<u8>, usize> = BTreeMap::new();
+        for (to_lock, to_amount) in to_lock_vec.into_iter().zip(to_amount_vec) {
+            match recipient_indices.entry(to_lock.as_slice().to_vec()) {
+                Entry::Occupied(entry) => {
+                    let amount = &mut recipients[*entry.get()].1;
+                    *amount = amount
+                        .checked_add(to_amount)
+                        .ok_or(Error::SSRIMethodsArgsInvalid)?;
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(recipients.len());
+                    recipients.push((to_lock, to_amount));
+                }
+            }
+        }
+        debug!("airdrop recipients after merging: {}", recipients.len());
+
+        let tx = tx.unwrap_or_default();
+        let udt_type = load_script()?;
+        let metadata_cell_dep = Self::metadata_cell_dep()?;
+        let batch_count = recipients.len().div_ceil(max_outputs as usize);
+        let chain_lock = if batch_count > 1 {
+            Some(Self::owner_lock(&tx)?)
+        } else {
+            None
+        };
+        let raw = tx.raw();
+        let context = RawTransaction::new_builder()
+            .version(raw.version())
+            .cell_deps(raw.cell_deps())
+            .header_deps(raw.header_deps())
+            .build();
+
+        let mut batch_tx = tx;
+        let mut tx_vec_builder = TransactionVec::new_builder();
+        for (index, batch) in recipients.chunks(max_outputs as usize).enumerate() {
+            let (batch_locks, batch_amounts): (Vec<_>, Vec<_>) = batch.iter().cloned().unzip();
+            let mut minted = Self::append_udt_outputs(
+                Some(batch_tx),
+                udt_type.clone(),
+                &batch_locks,
+                &batch_amounts,
+                metadata_cell_dep.clone(),
+            )?;
+            batch_tx = Transaction::new_builder().raw(context.clone()).build();
+            if let (Some(chain_lock), true) = (&chain_lock, index + 1 < batch_count) {
+                let raw = minted.raw();
+                let chain_output = CellOutputBuilder::default()
+                    .lock(chain_lock.clone())
+                    .build();
+                let outputs = raw.outputs().as_builder().push(chain_output).build();
+                let outputs_data = raw
+                    .outputs_data()
+                    .as_builder()
+                    .push(Default::default())
+                    .build();
+                minted = minted
+                    .as_builder()
+                    .raw(
+                        raw.as_builder()
+                            .outputs(outputs)
+                            .outputs_data(outputs_data)
+                            .build(),
+                    )
+                    .build();
+            }
+            tx_vec_builder = tx_vec_builder.push(minted);
+        }
+        Ok(tx_vec_builder.build())
+    }
+
+    /// The lock of the metadata owner, for outputs that must stay spendable
+    /// by them. Immutable args only commit to its hash, so the lock is taken
+    /// from an input or output of `tx` carrying it.
+    fn owner_lock(tx: &Transaction) -> Result<Script, Error> {
+        let UDTArgs::Immutable {
+            owner_lock_hash, ..
+        } = udt_args()?
+        else {
+            return Ok(find_cell_by_out_point(&SSRIMetadata::search_outpoint()?)?.lock());
+        };
+        let raw = tx.raw();
+        raw.inputs()
+            .into_iter()
+            .filter_map(|input| find_cell_by_out_point(&input.previous_output()).ok())
+            .chain(raw.outputs())
+            .map(|cell| cell.lock())
+            .find(|lock| lock.calc_script_hash().as_slice() == owner_lock_hash)
+            .ok_or(Error::NoMintPermission)
+    }
+
+    // #[ssri_method(level = "transaction")]
+    /// Runs the same checks as `fallback` against the supplied transaction and
+    /// reports the failure instead of exiting with it.
+    pub fn verify() -> Result<VerifyResult, Error> {
+        debug!("Entered SSRIUDT::verify");
+        load_tx_hash().map_err(|_| Error::SSRIMethodRequireHigherLevel)?;
+
+        Ok(match fallback() {
+            Ok(()) => VerifyResult {
+                passed: true,
+                error_code: 0,
+                error: Default::default(),
+            },
+            Err(err) => VerifyResult {
+                passed: false,
+                error: format!("{:?}", err),
+                error_code: err as u8,
+            },
+        })
+    }
+}
+
+// #[ssri_module]
+impl UDT for SSRIUDT {
+    type Error = Error;
+
+    // #[ssri_method(level = "script", transaction = true)]
+    fn transfer(
+        tx: Option<Transaction>,
+        to_lock_vec: Vec<Script>,
+        to_amount_vec: Vec<u128>,
+    ) -> Result<Transaction, Error> {
+        debug!("Entered UDT::transfer");
+        Self::append_udt_outputs(tx, load_script()?, &to_lock_vec, &to_amount_vec, None)
+    }
+
+    fn verify_transfer() -> Result<(), Self::Error> {
+        debug!("Entered UDT::verify_transfer");
+        Self::verify_transfer_amounts(collect_inputs_amount()?, collect_outputs_amount()?)
     }
 
     // #[ssri_method(level = "script")]
@@ -229,10 +602,30 @@ impl UDT for SSRIUDT {
     }
 
     // #[ssri_method(level = "script")]
+    /// Returns the inline icon, or the data of the cell `icon_ref` points to.
     fn icon() -> Result<Bytes, Self::Error> {
         let ssri_metadata =
             SSRIMetadata::new_from_onchain_search().map_err(|_| Error::SSRIConfigNotFound)?;
-        Ok(ssri_metadata.icon.into_bytes().into())
+        match ssri_metadata.icon_ref {
+            None => Ok(ssri_metadata.icon.into_bytes().into()),
+            Some(IconRef::TypeId(type_id_args)) => {
+                let out_point = find_out_point_by_type(type_id_script(&type_id_args))
+                    .map_err(|_| Error::MetadataIconNotFound)?;
+                Ok(find_cell_data_by_out_point(&out_point)?.into())
+            }
+            Some(IconRef::DataHash(data_ref)) => {
+                let out_point = OutPoint::new_builder()
+                    .tx_hash(data_ref.tx_hash.pack())
+                    .index(data_ref.index.pack())
+                    .build();
+                let icon = find_cell_data_by_out_point(&out_point)
+                    .map_err(|_| Error::MetadataIconNotFound)?;
+                if !data_ref.matches(&icon) {
+                    return Err(Error::MetadataIconHashMismatch);
+                }
+                Ok(icon.into())
+            }
+        }
     }
 
     // #[ssri_method(level = "script", transaction = true)]
@@ -242,102 +635,41 @@ impl UDT for SSRIUDT {
         to_amount_vec: Vec<u128>,
     ) -> Result<Transaction, Error> {
         debug!("Entered UDT::mint");
-        let tx_builder = match tx {
-            Some(ref tx) => tx.clone().as_builder(),
-            None => TransactionBuilder::default(),
-        };
-        let raw_tx_builder = match tx {
-            Some(ref tx) => tx.clone().raw().as_builder(),
-            None => RawTransactionBuilder::default(),
-        };
-
-        let mut cell_output_vec_builder = match tx {
-            Some(ref tx) => tx.clone().raw().outputs().as_builder(),
-            None => CellOutputVecBuilder::default(),
-        };
-
-        for to_lock in to_lock_vec.iter() {
-            let new_mint_output = CellOutputBuilder::default()
-                .type_(
-                    ScriptOptBuilder::default()
-                        .set(Some(load_script()?))
-                        .build(),
-                )
-                .lock(to_lock.clone())
-                .build();
-            cell_output_vec_builder = cell_output_vec_builder.push(new_mint_output);
-        }
-
-        let mut outputs_data_builder = match tx {
-            Some(ref tx) => tx.clone().raw().outputs_data().as_builder(),
-            None => BytesVecBuilder::default(),
-        };
-
-        for to_amount in to_amount_vec.iter() {
-            outputs_data_builder = outputs_data_builder.push(to_amount.pack().as_bytes().pack());
-        }
-
-        let mut cell_dep_vec_builder: CellDepVecBuilder = match tx {
-            Some(ref tx) => tx.clone().raw().cell_deps().as_builder(),
-            None => CellDepVecBuilder::default(),
-        };
-
-        let ssri_metadata_outpoint = SSRIMetadata::search_outpoint()?;
-        let ssri_metadata_celldep = CellDep::new_builder()
-            .out_point(ssri_metadata_outpoint)
-            .build();
-        cell_dep_vec_builder = cell_dep_vec_builder.push(ssri_metadata_celldep);
-
-        Ok(tx_builder
-            .raw(
-                raw_tx_builder
-                    .version(
-                        tx.clone()
-                            .map(|t| t.raw().version())
-                            .unwrap_or_else(|| Uint32::default()),
-                    )
-                    .cell_deps(cell_dep_vec_builder.build())
-                    .header_deps(
-                        tx.clone()
-                            .map(|t| t.raw().header_deps())
-                            .unwrap_or_else(|| Byte32Vec::default()),
-                    )
-                    .inputs(
-                        tx.clone()
-                            .map(|t| t.raw().inputs())
-                            .unwrap_or_else(|| CellInputVec::default()),
-                    )
-                    .outputs(cell_output_vec_builder.build())
-                    .outputs_data(outputs_data_builder.build())
-                    .build(),
-            )
-            .witnesses(
-                tx.clone()
-                    .map(|t| t.witnesses())
-                    .unwrap_or_else(|| BytesVec::default()),
-            )
-            .build())
+        let ssri_metadata_celldep = Self::metadata_cell_dep()?;
+        Self::append_udt_outputs(
+            tx,
+            load_script()?,
+            &to_lock_vec,
+            &to_amount_vec,
+            ssri_metadata_celldep,
+        )
     }
 
     fn verify_mint() -> Result<(), Self::Error> {
         debug!("Entered UDT::verify_mint");
-        let script = load_script()?;
-        let ssri_config_typeid_args: [u8; 32] = script
-            .args()
-            .raw_data()
-            .to_vec()
-            .try_into()
-            .map_err(|_| Error::InvalidUDTArgs)?;
-        let Some((ssri_config_cell, config_data)) =
-            find_ssri_config_cell(&ssri_config_typeid_args, Source::CellDep).or(
-                find_ssri_config_cell(&ssri_config_typeid_args, Source::Output),
-            )?
-        else {
-            return Err(Error::SSRIConfigNotFound);
+        let args = udt_args()?;
+        let (owner_lockhash, config_data) = match args {
+            // Immutable args name the owner themselves, and the cell dep
+            // holding the committed data is the metadata.
+            UDTArgs::Immutable {
+                owner_lock_hash, ..
+            } => (
+                owner_lock_hash,
+                find_committed_metadata(&args, Source::CellDep)?
+                    .ok_or(Error::SSRIConfigNotFound)?,
+            ),
+            // See `resolve_ssri_config_cell` for which cell decides; an output
+            // lets `create_and_mint` mint in the transaction that creates it.
+            _ => resolve_ssri_config_cell(&args)?,
         };
-        from_slice::<SSRIMetadata>(&config_data, false)
-            .map_err(|_| Error::SSRIConfigInvalidDataFormat)?;
-        let owner_lockhash = ssri_config_cell.lock().calc_script_hash().unpack();
+        let metadata =
+            SSRIMetadata::decode(&config_data).map_err(|_| Error::SSRIConfigInvalidDataFormat)?;
+        // Cells in an older layout predate the metadata rules and keep
+        // minting as they are. `migrate_metadata` applies the rules when it
+        // moves them to the current layout.
+        if metadata.version >= CURRENT_METADATA_VERSION {
+            metadata.validate()?;
+        }
         if check_owner_mode(&owner_lockhash)? {
             return Ok(());
         } else {
@@ -1,50 +1,67 @@
-use crate::{config::TYPE_ID_SCRIPT_CODE_HASH, error::Error};
-use alloc::vec::Vec;
-use ckb_ssri_std::public_module_traits::udt::UDT_LEN;
+use crate::error::Error;
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use ckb_ssri_std::{public_module_traits::udt::UDT_LEN, utils::syscalls};
 use ckb_std::{
     ckb_constants::Source,
     ckb_types::{
-        core::ScriptHashType,
-        packed::{CellOutput, Script},
-        prelude::{Builder, Entity, Pack},
+        packed::{CellOutput, CellOutputReader, OutPoint, Script},
+        prelude::{Entity, Reader, Unpack},
     },
     debug,
-    high_level::{load_cell, load_cell_data, load_cell_lock_hash, load_cell_type, QueryIter},
+    error::SysError,
+    high_level::{
+        load_cell, load_cell_data, load_cell_data_hash, load_cell_lock_hash, load_cell_type,
+        load_script, QueryIter, BUF_SIZE,
+    },
 };
+use ssri_udt_sdk::UDTArgs;
 
-pub fn collect_inputs_amount() -> Result<u128, Error> {
-    debug!("Entered collect_inputs_amount");
+/// Loads the UDT amount of cell `index` in `source`, reading only the first
+/// `UDT_LEN` bytes of its data. Returns `None` past the last cell.
+fn load_amount(index: usize, source: Source) -> Result<Option<u128>, Error> {
     let mut buf = [0u8; UDT_LEN];
+    match ckb_std::syscalls::load_cell_data(&mut buf, 0, index, source) {
+        Ok(UDT_LEN) => Ok(Some(u128::from_le_bytes(buf))),
+        Ok(_) | Err(SysError::LengthNotEnough(_)) => Err(Error::Encoding),
+        Err(SysError::IndexOutOfBound) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
 
-    let udt_list = QueryIter::new(load_cell_data, Source::GroupInput)
-        .map(|data| {
-            if data.len() == UDT_LEN {
-                buf.copy_from_slice(&data);
-                Ok(u128::from_le_bytes(buf))
-            } else {
-                Err(Error::Encoding)
-            }
-        })
-        .collect::<Result<Vec<_>, Error>>()?;
-    Ok(udt_list.into_iter().sum::<u128>())
+/// Sums the UDT of the group cells in `source` in a single pass, failing with
+/// `AmountOverflow` rather than wrapping.
+pub fn collect_amount(source: Source) -> Result<u128, Error> {
+    let mut total = 0u128;
+    let mut index = 0;
+    while let Some(amount) = load_amount(index, source)? {
+        total = total.checked_add(amount).ok_or(Error::AmountOverflow)?;
+        index += 1;
+    }
+    Ok(total)
+}
+
+pub fn collect_inputs_amount() -> Result<u128, Error> {
+    debug!("Entered collect_inputs_amount");
+    collect_amount(Source::GroupInput)
 }
 
 pub fn collect_outputs_amount() -> Result<u128, Error> {
     debug!("Entered collect_outputs_amount");
-    let mut buf = [0u8; UDT_LEN];
+    collect_amount(Source::GroupOutput)
+}
 
-    let udt_list = QueryIter::new(load_cell_data, Source::GroupOutput)
-        .map(|data| {
-            if data.len() == UDT_LEN {
-                buf.copy_from_slice(&data);
-                // u128 is 16 bytes
-                Ok(u128::from_le_bytes(buf))
-            } else {
-                Err(Error::Encoding)
-            }
-        })
-        .collect::<Result<Vec<_>, Error>>()?;
-    Ok(udt_list.into_iter().sum::<u128>())
+/// Sums the UDT of the group cells in `source` per lock hash.
+pub fn collect_amounts_by_lock(source: Source) -> Result<BTreeMap<[u8; 32], u128>, Error> {
+    debug!("Entered collect_amounts_by_lock");
+    let mut amounts = BTreeMap::new();
+    let mut index = 0;
+    while let Some(amount) = load_amount(index, source)? {
+        let lock_hash = load_cell_lock_hash(index, source)?;
+        let entry: &mut u128 = amounts.entry(lock_hash).or_insert(0);
+        *entry = entry.checked_add(amount).ok_or(Error::AmountOverflow)?;
+        index += 1;
+    }
+    Ok(amounts)
 }
 
 pub fn check_owner_mode(owner_lockhash: &[u8; 32]) -> Result<bool, Error> {
@@ -56,29 +73,127 @@ pub fn check_owner_mode(owner_lockhash: &[u8; 32]) -> Result<bool, Error> {
     Ok(is_owner_mode)
 }
 
+/// Parses the args of the running script.
+pub fn udt_args() -> Result<UDTArgs, Error> {
+    UDTArgs::parse(&load_script()?.args().raw_data()).ok_or(Error::InvalidUDTArgs)
+}
+
+/// Finds the metadata cell data that immutable `args` commit to. Cells are
+/// matched by data hash, so large ones such as code cell deps are never
+/// loaded.
+pub fn find_committed_metadata(args: &UDTArgs, source: Source) -> Result<Option<Vec<u8>>, Error> {
+    let UDTArgs::Immutable { metadata_hash, .. } = args else {
+        return Ok(None);
+    };
+    match QueryIter::new(load_cell_data_hash, source).position(|hash| hash == *metadata_hash) {
+        Some(index) => Ok(Some(load_cell_data(index, source)?)),
+        None => Ok(None),
+    }
+}
+
+/// Finds the config cell named by `args` in `source`, looking it up by the
+/// singleton type script the args choose. Identical matches, such as one cell
+/// dep listed twice, count as one; matches that differ in any way fail with
+/// `ConflictingSSRIConfig`.
 pub fn find_ssri_config_cell(
-    type_id_args: &[u8; 32],
+    args: &UDTArgs,
     source: Source,
 ) -> Result<Option<(CellOutput, Vec<u8>)>, Error> {
-    let ssri_type_script = Script::new_builder()
-        .code_hash(TYPE_ID_SCRIPT_CODE_HASH.pack())
-        .hash_type(ScriptHashType::Type.into())
-        .args(type_id_args.to_vec().pack())
-        .build();
-    let ssri_config_cell =
-        QueryIter::new(load_cell_type, source)
-            .enumerate()
-            .find_map(|(i, type_script)| match type_script {
-                Some(type_script) => {
-                    if type_script.as_slice() == ssri_type_script.as_slice() {
-                        let cell = load_cell(i, source).unwrap();
-                        let cell_data = load_cell_data(i, source).unwrap();
-                        Some((cell, cell_data))
-                    } else {
-                        None
-                    }
-                }
-                None => None,
-            });
+    let ssri_type_script = args
+        .metadata_type_script()
+        .ok_or(Error::ImmutableMetadata)?;
+    let mut ssri_config_cell: Option<(CellOutput, Vec<u8>)> = None;
+    for (i, type_script) in QueryIter::new(load_cell_type, source).enumerate() {
+        if type_script
+            .is_none_or(|type_script| type_script.as_slice() != ssri_type_script.as_slice())
+        {
+            continue;
+        }
+        let cell = (load_cell(i, source)?, load_cell_data(i, source)?);
+        match &ssri_config_cell {
+            None => ssri_config_cell = Some(cell),
+            Some(found) if found.0.as_slice() == cell.0.as_slice() && found.1 == cell.1 => {}
+            Some(_) => return Err(Error::ConflictingSSRIConfig),
+        }
+    }
     Ok(ssri_config_cell)
 }
+
+/// Resolves the config cell a mint is checked against, returning the owner
+/// lock hash and the metadata to validate.
+///
+/// - Cells in `Input` and `CellDep` are live before the transaction, so they
+///   must be the same cell. Its lock is the owner, even when the transaction
+///   updates or destroys it.
+/// - A cell in `Output` is the metadata after the transaction, and is what
+///   gets validated. Its lock is the owner only when no live cell exists,
+///   that is when the transaction creates the metadata.
+pub fn resolve_ssri_config_cell(args: &UDTArgs) -> Result<([u8; 32], Vec<u8>), Error> {
+    let live = match (
+        find_ssri_config_cell(args, Source::Input)?,
+        find_ssri_config_cell(args, Source::CellDep)?,
+    ) {
+        (Some(input), Some(cell_dep)) if input != cell_dep => {
+            return Err(Error::ConflictingSSRIConfig);
+        }
+        (input, cell_dep) => input.or(cell_dep),
+    };
+    let output = find_ssri_config_cell(args, Source::Output)?;
+    let owner_lock_hash = match (&live, &output) {
+        (Some((cell, _)), _) | (None, Some((cell, _))) => cell.lock().calc_script_hash().unpack(),
+        (None, None) => return Err(Error::SSRIConfigNotFound),
+    };
+    let (_, metadata_data) = output.or(live).ok_or(Error::SSRIConfigNotFound)?;
+    Ok((owner_lock_hash, metadata_data))
+}
+
+/// Resolves the lock hash owner mode is checked against: the one immutable
+/// `args` commit to, or the lock `resolve_ssri_config_cell` picks.
+pub fn resolve_owner_lock_hash(args: &UDTArgs) -> Result<[u8; 32], Error> {
+    match args {
+        UDTArgs::Immutable {
+            owner_lock_hash, ..
+        } => Ok(*owner_lock_hash),
+        _ => resolve_ssri_config_cell(args).map(|(owner_lock_hash, _)| owner_lock_hash),
+    }
+}
+
+const SHANNONS_PER_CKB: u64 = 100_000_000;
+
+/// Capacity `cell` occupies with `data_len` bytes of data, in shannons: one
+/// CKB per byte of its capacity field, lock, type and data.
+pub fn occupied_capacity(cell: &CellOutput, data_len: usize) -> u64 {
+    let script_len = |script: Script| 32 + 1 + script.args().raw_data().len();
+    let type_len = cell.type_().to_opt().map_or(0, script_len);
+    (8 + script_len(cell.lock()) + type_len + data_len) as u64 * SHANNONS_PER_CKB
+}
+
+/// Loads the whole result of an SSRI out point syscall. These syscalls ignore
+/// the offset, so data longer than the first buffer is loaded again from the
+/// start, unlike `ckb_ssri_std::utils::high_level`, which corrupts it.
+fn load_by_out_point(
+    syscall: fn(&mut [u8], &[u8]) -> Result<usize, SysError>,
+    out_point: &OutPoint,
+) -> Result<Vec<u8>, SysError> {
+    let mut buf = [0u8; BUF_SIZE];
+    match syscall(&mut buf, out_point.as_slice()) {
+        Ok(len) => Ok(buf[..len].to_vec()),
+        Err(SysError::LengthNotEnough(actual_size)) => {
+            let mut data = vec![0; actual_size];
+            let len = syscall(&mut data, out_point.as_slice())?;
+            data.truncate(len);
+            Ok(data)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+pub fn find_cell_by_out_point(out_point: &OutPoint) -> Result<CellOutput, SysError> {
+    let data = load_by_out_point(syscalls::find_cell_by_out_point, out_point)?;
+    CellOutputReader::verify(&data, false).map_err(|_| SysError::Encoding)?;
+    Ok(CellOutput::new_unchecked(data.into()))
+}
+
+pub fn find_cell_data_by_out_point(out_point: &OutPoint) -> Result<Vec<u8>, SysError> {
+    load_by_out_point(syscalls::find_cell_data_by_out_point, out_point)
+}
@@ -0,0 +1,172 @@
+//! Runs SSRI methods of a contract in an in-process ckb-vm, serving the SSRI
+//! syscalls (cell search, load by out point, pipe output) from a ckb-testtool
+//! [`Context`] instead of a live ssri-server and chain.
+
+use std::sync::{Arc, Mutex};
+
+use ckb_testtool::{
+    ckb_types::{
+        bytes::Bytes,
+        core::TransactionView,
+        packed::{CellOutput, OutPoint, Script},
+    },
+    context::Context,
+};
+use ckb_vm::{
+    cost_model::estimate_cycles, machine::VERSION2, DefaultCoreMachine, DefaultMachineBuilder,
+    SparseMemory, SupportMachine, WXorXMemory, ISA_B, ISA_IMC, ISA_MOP,
+};
+
+mod syscalls;
+
+use syscalls::{ResolvedTransaction, SSRISyscalls};
+
+pub const DEFAULT_MAX_CYCLES: u64 = 70_000_000;
+
+/// How much of the environment a method call can see, mirroring the
+/// `run_script_level_*` endpoints of ssri-server.
+pub enum Level {
+    /// Only the code cell; `load_script` is unavailable.
+    Code,
+    /// The code cell plus the script it runs as.
+    Script(Script),
+    /// A single cell, exposed as output 0 of its own script group.
+    Cell {
+        script: Script,
+        cell: CellOutput,
+        data: Bytes,
+    },
+    /// A full transaction whose inputs and cell deps are resolved from the
+    /// context.
+    Transaction { script: Script, tx: TransactionView },
+}
+
+#[derive(Debug)]
+pub enum ExecutionError {
+    CodeNotFound(OutPoint),
+    UnresolvedCell(OutPoint),
+    Vm(ckb_vm::Error),
+    /// The method returned a non-zero exit code, i.e. the contract `Error`.
+    ExitCode(i8),
+}
+
+impl std::fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CodeNotFound(out_point) => write!(f, "code cell {} not found", out_point),
+            Self::UnresolvedCell(out_point) => write!(f, "cell {} not found", out_point),
+            Self::Vm(err) => write!(f, "vm error: {:?}", err),
+            Self::ExitCode(code) => write!(f, "script exited with code {}", code),
+        }
+    }
+}
+
+impl From<ckb_vm::Error> for ExecutionError {
+    fn from(err: ckb_vm::Error) -> Self {
+        Self::Vm(err)
+    }
+}
+
+#[derive(Debug)]
+pub struct Execution {
+    /// Everything the method wrote to its pipe.
+    pub content: Bytes,
+    pub cycles: u64,
+    /// Messages the contract printed with `debug!`, in order. They are kept
+    /// here rather than printed, so callers decide whether to show them.
+    pub debug: Vec<String>,
+}
+
+pub struct SSRIExecutor<'a> {
+    context: &'a Context,
+    code: Bytes,
+    max_cycles: u64,
+}
+
+impl<'a> SSRIExecutor<'a> {
+    /// Loads the code from the cell at `code_out_point`, like ssri-server does
+    /// with its `tx_hash` and `index` parameters.
+    pub fn new(context: &'a Context, code_out_point: &OutPoint) -> Result<Self, ExecutionError> {
+        let (_, code) = context
+            .get_cell(code_out_point)
+            .ok_or_else(|| ExecutionError::CodeNotFound(code_out_point.clone()))?;
+        Ok(Self {
+            context,
+            code,
+            max_cycles: DEFAULT_MAX_CYCLES,
+        })
+    }
+
+    pub fn max_cycles(mut self, max_cycles: u64) -> Self {
+        self.max_cycles = max_cycles;
+        self
+    }
+
+    pub fn run_script_level_code(&self, argv: &[Vec<u8>]) -> Result<Execution, ExecutionError> {
+        self.run(argv, Level::Code)
+    }
+
+    pub fn run_script_level_script(
+        &self,
+        argv: &[Vec<u8>],
+        script: &Script,
+    ) -> Result<Execution, ExecutionError> {
+        self.run(argv, Level::Script(script.clone()))
+    }
+
+    /// Runs a method with raw argv; each argument is hex encoded before being
+    /// passed to the VM, as ssri-server does.
+    pub fn run(&self, argv: &[Vec<u8>], level: Level) -> Result<Execution, ExecutionError> {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let debug = Arc::new(Mutex::new(Vec::new()));
+        let (script, transaction) = match level {
+            Level::Code => (None, None),
+            Level::Script(script) => (Some(script), None),
+            Level::Cell { script, cell, data } => (
+                Some(script),
+                Some(ResolvedTransaction::from_cell(cell, data)),
+            ),
+            Level::Transaction { script, tx } => {
+                let resolved = ResolvedTransaction::resolve(self.context, tx)?;
+                (Some(script), Some(resolved))
+            }
+        };
+        let syscalls = SSRISyscalls::new(
+            self.context.cells.clone(),
+            script,
+            transaction,
+            Arc::clone(&output),
+            Arc::clone(&debug),
+        );
+
+        let core_machine = DefaultCoreMachine::<u64, WXorXMemory<SparseMemory<u64>>>::new(
+            ISA_IMC | ISA_B | ISA_MOP,
+            VERSION2,
+            self.max_cycles,
+        );
+        let mut machine = DefaultMachineBuilder::new(core_machine)
+            .instruction_cycle_func(Box::new(estimate_cycles))
+            .syscall(Box::new(syscalls))
+            .build();
+        let args = argv
+            .iter()
+            .map(|arg| Bytes::from(encode_hex(arg)))
+            .collect::<Vec<_>>();
+        machine.load_program(&self.code, &args)?;
+        let exit_code = machine.run()?;
+        if exit_code != 0 {
+            return Err(ExecutionError::ExitCode(exit_code));
+        }
+        let content = Bytes::from(output.lock().unwrap().clone());
+        let debug = debug.lock().unwrap().clone();
+        Ok(Execution {
+            content,
+            cycles: machine.cycles(),
+            debug,
+        })
+    }
+}
+
+fn encode_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,379 @@
+use std::{
+    cmp,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use ckb_testtool::{
+    ckb_types::{
+        bytes::Bytes,
+        core::TransactionView,
+        packed::{CellInput, CellOutput, OutPoint, Script},
+        prelude::*,
+    },
+    context::Context,
+};
+use ckb_vm::{
+    registers::{A0, A1, A2, A3, A4, A5, A7},
+    Error as VMError, Memory, Register, SupportMachine, Syscalls,
+};
+
+use crate::ExecutionError;
+
+const SYS_VM_VERSION: u64 = 2041;
+const SYS_LOAD_TRANSACTION: u64 = 2051;
+const SYS_LOAD_SCRIPT: u64 = 2052;
+const SYS_LOAD_TX_HASH: u64 = 2061;
+const SYS_LOAD_SCRIPT_HASH: u64 = 2062;
+const SYS_LOAD_CELL: u64 = 2071;
+const SYS_LOAD_INPUT: u64 = 2073;
+const SYS_LOAD_WITNESS: u64 = 2074;
+const SYS_LOAD_CELL_BY_FIELD: u64 = 2081;
+const SYS_LOAD_INPUT_BY_FIELD: u64 = 2083;
+const SYS_LOAD_CELL_DATA: u64 = 2092;
+const SYS_DEBUG: u64 = 2177;
+const SYS_FIND_OUT_POINT_BY_TYPE: u64 = 2277;
+const SYS_FIND_CELL_BY_OUT_POINT: u64 = 2287;
+const SYS_FIND_CELL_DATA_BY_OUT_POINT: u64 = 2297;
+const SYS_PIPE: u64 = 2604;
+const SYS_WRITE: u64 = 2605;
+
+const SUCCESS: u64 = 0;
+const INDEX_OUT_OF_BOUND: u64 = 1;
+const ITEM_MISSING: u64 = 2;
+
+const SOURCE_INPUT: u64 = 1;
+const SOURCE_OUTPUT: u64 = 2;
+const SOURCE_CELL_DEP: u64 = 3;
+const SOURCE_GROUP_INPUT: u64 = 0x0100000000000001;
+const SOURCE_GROUP_OUTPUT: u64 = 0x0100000000000002;
+
+const FIELD_CAPACITY: u64 = 0;
+const FIELD_DATA_HASH: u64 = 1;
+const FIELD_LOCK: u64 = 2;
+const FIELD_LOCK_HASH: u64 = 3;
+const FIELD_TYPE: u64 = 4;
+const FIELD_TYPE_HASH: u64 = 5;
+
+const INPUT_FIELD_OUT_POINT: u64 = 0;
+const INPUT_FIELD_SINCE: u64 = 1;
+
+const PIPE_FDS: [u64; 2] = [2, 3];
+
+/// Data to store and the offset to store it from, or a syscall error code.
+type Loaded = Result<(Bytes, u64), u64>;
+
+/// Transaction visible to a cell or transaction level call, with every input
+/// and cell dep resolved to its cell.
+pub struct ResolvedTransaction {
+    tx: Option<TransactionView>,
+    inputs: Vec<(CellOutput, Bytes)>,
+    outputs: Vec<(CellOutput, Bytes)>,
+    cell_deps: Vec<(CellOutput, Bytes)>,
+}
+
+impl ResolvedTransaction {
+    pub fn from_cell(cell: CellOutput, data: Bytes) -> Self {
+        Self {
+            tx: None,
+            inputs: vec![],
+            outputs: vec![(cell, data)],
+            cell_deps: vec![],
+        }
+    }
+
+    pub fn resolve(context: &Context, tx: TransactionView) -> Result<Self, ExecutionError> {
+        let resolve_cell = |out_point: OutPoint| {
+            context
+                .get_cell(&out_point)
+                .ok_or(ExecutionError::UnresolvedCell(out_point))
+        };
+        let inputs = tx
+            .input_pts_iter()
+            .map(resolve_cell)
+            .collect::<Result<Vec<_>, _>>()?;
+        let cell_deps = tx
+            .cell_deps_iter()
+            .map(|cell_dep| resolve_cell(cell_dep.out_point()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let outputs = tx.outputs_with_data_iter().collect();
+        Ok(Self {
+            tx: Some(tx),
+            inputs,
+            outputs,
+            cell_deps,
+        })
+    }
+}
+
+pub struct SSRISyscalls {
+    chain: HashMap<OutPoint, (CellOutput, Bytes)>,
+    script: Option<Script>,
+    transaction: Option<ResolvedTransaction>,
+    output: Arc<Mutex<Vec<u8>>>,
+    debug: Arc<Mutex<Vec<String>>>,
+}
+
+impl SSRISyscalls {
+    pub fn new(
+        chain: HashMap<OutPoint, (CellOutput, Bytes)>,
+        script: Option<Script>,
+        transaction: Option<ResolvedTransaction>,
+        output: Arc<Mutex<Vec<u8>>>,
+        debug: Arc<Mutex<Vec<String>>>,
+    ) -> Self {
+        Self {
+            chain,
+            script,
+            transaction,
+            output,
+            debug,
+        }
+    }
+
+    fn in_group(&self, cell: &CellOutput) -> bool {
+        let Some(script) = &self.script else {
+            return false;
+        };
+        cell.lock().as_slice() == script.as_slice()
+            || cell
+                .type_()
+                .to_opt()
+                .is_some_and(|type_| type_.as_slice() == script.as_slice())
+    }
+
+    /// Maps an `(index, source)` pair to the index among inputs or outputs.
+    fn locate(&self, index: u64, source: u64) -> Result<(u64, usize), u64> {
+        let transaction = self.transaction.as_ref().ok_or(ITEM_MISSING)?;
+        let index = index as usize;
+        let group_index = |cells: &[(CellOutput, Bytes)]| {
+            cells
+                .iter()
+                .enumerate()
+                .filter(|(_, (cell, _))| self.in_group(cell))
+                .nth(index)
+                .map(|(i, _)| i)
+                .ok_or(INDEX_OUT_OF_BOUND)
+        };
+        match source {
+            SOURCE_INPUT | SOURCE_OUTPUT | SOURCE_CELL_DEP => Ok((source, index)),
+            SOURCE_GROUP_INPUT => Ok((SOURCE_INPUT, group_index(&transaction.inputs)?)),
+            SOURCE_GROUP_OUTPUT => Ok((SOURCE_OUTPUT, group_index(&transaction.outputs)?)),
+            _ => Err(INDEX_OUT_OF_BOUND),
+        }
+    }
+
+    fn cell(&self, index: u64, source: u64) -> Result<&(CellOutput, Bytes), u64> {
+        let transaction = self.transaction.as_ref().ok_or(ITEM_MISSING)?;
+        let (source, index) = self.locate(index, source)?;
+        let cells = match source {
+            SOURCE_INPUT => &transaction.inputs,
+            SOURCE_OUTPUT => &transaction.outputs,
+            _ => &transaction.cell_deps,
+        };
+        cells.get(index).ok_or(INDEX_OUT_OF_BOUND)
+    }
+
+    fn tx(&self) -> Result<&TransactionView, u64> {
+        self.transaction
+            .as_ref()
+            .and_then(|transaction| transaction.tx.as_ref())
+            .ok_or(ITEM_MISSING)
+    }
+
+    fn input(&self, index: u64, source: u64) -> Result<CellInput, u64> {
+        match self.locate(index, source)? {
+            (SOURCE_INPUT, index) => self.tx()?.inputs().get(index).ok_or(INDEX_OUT_OF_BOUND),
+            _ => Err(INDEX_OUT_OF_BOUND),
+        }
+    }
+
+    fn cell_field(&self, index: u64, source: u64, field: u64) -> Result<Bytes, u64> {
+        let (cell, data) = self.cell(index, source)?;
+        match field {
+            FIELD_CAPACITY => Ok(cell.capacity().as_bytes()),
+            FIELD_DATA_HASH => Ok(CellOutput::calc_data_hash(data).as_bytes()),
+            FIELD_LOCK => Ok(cell.lock().as_bytes()),
+            FIELD_LOCK_HASH => Ok(cell.lock().calc_script_hash().as_bytes()),
+            FIELD_TYPE => cell
+                .type_()
+                .to_opt()
+                .map(|type_| type_.as_bytes())
+                .ok_or(ITEM_MISSING),
+            FIELD_TYPE_HASH => cell
+                .type_()
+                .to_opt()
+                .map(|type_| type_.calc_script_hash().as_bytes())
+                .ok_or(ITEM_MISSING),
+            _ => Err(INDEX_OUT_OF_BOUND),
+        }
+    }
+
+    fn find_out_point_by_type(&self, type_script: &[u8]) -> Result<Bytes, u64> {
+        // The chain is a map, so pick the smallest match to stay deterministic.
+        self.chain
+            .iter()
+            .filter(|(_, (cell, _))| {
+                cell.type_()
+                    .to_opt()
+                    .is_some_and(|type_| type_.as_slice() == type_script)
+            })
+            .map(|(out_point, _)| out_point.as_bytes())
+            .min()
+            .ok_or(ITEM_MISSING)
+    }
+
+    fn chain_cell(&self, out_point: &[u8]) -> Result<&(CellOutput, Bytes), u64> {
+        let out_point = OutPoint::from_slice(out_point).map_err(|_| INDEX_OUT_OF_BOUND)?;
+        self.chain.get(&out_point).ok_or(ITEM_MISSING)
+    }
+
+    fn load<Mac: SupportMachine>(
+        &self,
+        machine: &mut Mac,
+        code: u64,
+    ) -> Result<Option<Loaded>, VMError> {
+        let [a2, a3, a4, a5] = [A2, A3, A4, A5].map(|i| machine.registers()[i].to_u64());
+        let loaded = match code {
+            SYS_LOAD_SCRIPT => self
+                .script
+                .as_ref()
+                .map(|script| script.as_bytes())
+                .ok_or(ITEM_MISSING),
+            SYS_LOAD_SCRIPT_HASH => self
+                .script
+                .as_ref()
+                .map(|script| script.calc_script_hash().as_bytes())
+                .ok_or(ITEM_MISSING),
+            SYS_LOAD_TRANSACTION => self.tx().map(|tx| tx.data().as_bytes()),
+            SYS_LOAD_TX_HASH => self.tx().map(|tx| tx.hash().as_bytes()),
+            SYS_LOAD_CELL => self.cell(a3, a4).map(|(cell, _)| cell.as_bytes()),
+            SYS_LOAD_CELL_DATA => self.cell(a3, a4).map(|(_, data)| data.clone()),
+            SYS_LOAD_CELL_BY_FIELD => self.cell_field(a3, a4, a5),
+            SYS_LOAD_INPUT => self.input(a3, a4).map(|input| input.as_bytes()),
+            SYS_LOAD_INPUT_BY_FIELD => self.input(a3, a4).and_then(|input| match a5 {
+                INPUT_FIELD_OUT_POINT => Ok(input.previous_output().as_bytes()),
+                INPUT_FIELD_SINCE => Ok(input.since().as_bytes()),
+                _ => Err(INDEX_OUT_OF_BOUND),
+            }),
+            SYS_LOAD_WITNESS => self.tx().and_then(|tx| {
+                let (_, index) = self.locate(a3, a4)?;
+                tx.witnesses()
+                    .get(index)
+                    .map(|witness| witness.raw_data())
+                    .ok_or(INDEX_OUT_OF_BOUND)
+            }),
+            // The SSRI search syscalls carry a pointer in A2 instead of an
+            // offset, and always load from the start.
+            SYS_FIND_OUT_POINT_BY_TYPE => {
+                let type_script = machine.memory_mut().load_bytes(a2, a3)?;
+                return Ok(Some(
+                    self.find_out_point_by_type(&type_script)
+                        .map(|data| (data, 0)),
+                ));
+            }
+            SYS_FIND_CELL_BY_OUT_POINT | SYS_FIND_CELL_DATA_BY_OUT_POINT => {
+                let out_point = machine
+                    .memory_mut()
+                    .load_bytes(a2, OutPoint::TOTAL_SIZE as u64)?;
+                return Ok(Some(self.chain_cell(&out_point).map(|(cell, data)| {
+                    if code == SYS_FIND_CELL_BY_OUT_POINT {
+                        (cell.as_bytes(), 0)
+                    } else {
+                        (data.clone(), 0)
+                    }
+                })));
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(loaded.map(|data| (data, a2))))
+    }
+}
+
+fn store_data<Mac: SupportMachine>(
+    machine: &mut Mac,
+    data: &[u8],
+    offset: u64,
+) -> Result<(), VMError> {
+    let addr = machine.registers()[A0].to_u64();
+    let size_addr = machine.registers()[A1].clone();
+    let data_len = data.len() as u64;
+    let offset = cmp::min(data_len, offset);
+
+    let size = machine.memory_mut().load64(&size_addr)?.to_u64();
+    let full_size = data_len - offset;
+    let real_size = cmp::min(size, full_size);
+    machine
+        .memory_mut()
+        .store64(&size_addr, &Mac::REG::from_u64(full_size))?;
+    machine
+        .memory_mut()
+        .store_bytes(addr, &data[offset as usize..(offset + real_size) as usize])?;
+    Ok(())
+}
+
+fn load_c_string<Mac: SupportMachine>(machine: &mut Mac, mut addr: u64) -> Result<Bytes, VMError> {
+    let mut buffer = Vec::new();
+    loop {
+        let byte = machine
+            .memory_mut()
+            .load8(&Mac::REG::from_u64(addr))?
+            .to_u8();
+        if byte == 0 {
+            break;
+        }
+        buffer.push(byte);
+        addr += 1;
+    }
+    Ok(Bytes::from(buffer))
+}
+
+impl<Mac: SupportMachine> Syscalls<Mac> for SSRISyscalls {
+    fn initialize(&mut self, _machine: &mut Mac) -> Result<(), VMError> {
+        Ok(())
+    }
+
+    fn ecall(&mut self, machine: &mut Mac) -> Result<bool, VMError> {
+        let code = machine.registers()[A7].to_u64();
+        let ret = match code {
+            // SSRI calls are told apart from on-chain runs by this version.
+            SYS_VM_VERSION => u64::MAX,
+            SYS_DEBUG => {
+                let message = load_c_string(machine, machine.registers()[A0].to_u64())?;
+                self.debug
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&message).into_owned());
+                SUCCESS
+            }
+            SYS_PIPE => {
+                let fds_addr = machine.registers()[A0].to_u64();
+                for (i, fd) in PIPE_FDS.iter().enumerate() {
+                    machine.memory_mut().store64(
+                        &Mac::REG::from_u64(fds_addr + 8 * i as u64),
+                        &Mac::REG::from_u64(*fd),
+                    )?;
+                }
+                SUCCESS
+            }
+            SYS_WRITE => {
+                let buffer_addr = machine.registers()[A1].to_u64();
+                let length_addr = machine.registers()[A2].clone();
+                let length = machine.memory_mut().load64(&length_addr)?.to_u64();
+                let data = machine.memory_mut().load_bytes(buffer_addr, length)?;
+                self.output.lock().unwrap().extend_from_slice(&data);
+                SUCCESS
+            }
+            _ => match self.load(machine, code)? {
+                None => return Ok(false),
+                Some(Ok((data, offset))) => {
+                    store_data(machine, &data, offset)?;
+                    SUCCESS
+                }
+                Some(Err(err)) => err,
+            },
+        };
+        machine.set_register(A0, Mac::REG::from_u64(ret));
+        Ok(true)
+    }
+}
@@ -0,0 +1,248 @@
+//! A local stand-in for [ssri-server](https://github.com/ckb-devrel/ssri-server).
+//!
+//! Deployed cells are described by a JSON or TOML fixture instead of being
+//! fetched from a node, and methods run in-process through `ssri-executor`.
+//! The `run_script_level_*` JSON-RPC methods take the same parameters and
+//! return the same `{ "content": "0x..." }` result as ssri-server.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use ckb_jsonrpc_types::{CellOutput, JsonBytes, OutPoint, Script, Transaction};
+use ckb_testtool::{
+    ckb_types::{bytes::Bytes, core::TransactionView, packed, prelude::*, H256},
+    context::Context,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use ssri_executor::{ExecutionError, Level, SSRIExecutor};
+use ssri_udt_sdk::SSRIMetadata;
+
+// JSON-RPC 2.0 error codes
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// The method ran but failed; `data` carries the script exit code if any.
+pub const EXECUTION_ERROR: i64 = -32000;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Fixture {
+    #[serde(default)]
+    cells: Vec<FixtureCell>,
+}
+
+/// A live cell on the fixture chain. Exactly one of `data`, `data_file` or
+/// `metadata` may be set; none means empty data.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureCell {
+    out_point: OutPoint,
+    output: CellOutput,
+    data: Option<JsonBytes>,
+    /// Read from disk, relative to the fixture file, e.g. a contract binary.
+    data_file: Option<PathBuf>,
+    /// Encoded with serde_molecule, as stored in the SSRI metadata cell.
+    metadata: Option<SSRIMetadata>,
+}
+
+#[derive(Debug)]
+pub enum FixtureError {
+    Io(PathBuf, std::io::Error),
+    Json(serde_json::Error),
+    Toml(toml::de::Error),
+    /// The cell at this position in `cells` is malformed.
+    InvalidCell(usize, &'static str),
+}
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "failed to read {}: {}", path.display(), err),
+            Self::Json(err) => write!(f, "invalid JSON fixture: {}", err),
+            Self::Toml(err) => write!(f, "invalid TOML fixture: {}", err),
+            Self::InvalidCell(index, reason) => write!(f, "cells[{}]: {}", index, reason),
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {}
+
+/// Builds a context holding every cell of the fixture at `path`. Files ending
+/// in `.toml` are parsed as TOML, anything else as JSON.
+pub fn load_fixture(path: &Path) -> Result<Context, FixtureError> {
+    let content =
+        fs::read_to_string(path).map_err(|err| FixtureError::Io(path.to_path_buf(), err))?;
+    let fixture: Fixture = if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str(&content).map_err(FixtureError::Toml)?
+    } else {
+        serde_json::from_str(&content).map_err(FixtureError::Json)?
+    };
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+
+    let mut context = Context::default();
+    for (index, cell) in fixture.cells.into_iter().enumerate() {
+        let data = match (cell.data, cell.data_file, cell.metadata) {
+            (None, None, None) => Bytes::new(),
+            (Some(data), None, None) => data.into_bytes(),
+            (None, Some(data_file), None) => {
+                let data_path = base_dir.join(data_file);
+                fs::read(&data_path)
+                    .map_err(|err| FixtureError::Io(data_path, err))?
+                    .into()
+            }
+            (None, None, Some(metadata)) => serde_molecule::to_vec(&metadata, false)
+                .map_err(|_| FixtureError::InvalidCell(index, "metadata cannot be encoded"))?
+                .into(),
+            _ => {
+                return Err(FixtureError::InvalidCell(
+                    index,
+                    "only one of data, data_file and metadata can be set",
+                ))
+            }
+        };
+        let out_point: packed::OutPoint = cell.out_point.into();
+        if context.cells.contains_key(&out_point) {
+            return Err(FixtureError::InvalidCell(index, "duplicated out_point"));
+        }
+        context.create_cell_with_out_point(out_point, cell.output.into(), data);
+    }
+    Ok(context)
+}
+
+/// The cell passed to `run_script_level_cell`, named as in ssri-server.
+#[derive(Deserialize)]
+struct CellOutputWithData {
+    cell_output: CellOutput,
+    hex_data: Option<JsonBytes>,
+}
+
+type CodeParams = (H256, u32, Vec<JsonBytes>);
+
+struct RpcError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<ExecutionError> for RpcError {
+    fn from(err: ExecutionError) -> Self {
+        let data = match err {
+            ExecutionError::ExitCode(code) => Some(json!({ "exit_code": code })),
+            _ => None,
+        };
+        Self {
+            code: EXECUTION_ERROR,
+            message: err.to_string(),
+            data,
+        }
+    }
+}
+
+/// Handles a single JSON-RPC request object and returns the response object.
+pub fn handle_request(context: &Context, request: &Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let result = match (
+        request.get("method").and_then(Value::as_str),
+        request.get("params"),
+    ) {
+        (Some(method), Some(params)) => dispatch(context, method, params.clone()),
+        _ => Err(RpcError::new(INVALID_REQUEST, "invalid request")),
+    };
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => {
+            let mut error = json!({ "code": err.code, "message": err.message });
+            if let Some(data) = err.data {
+                error["data"] = data;
+            }
+            json!({ "jsonrpc": "2.0", "id": id, "error": error })
+        }
+    }
+}
+
+/// Parses a raw request body, answering malformed JSON with a parse error.
+pub fn handle_body(context: &Context, body: &[u8]) -> Value {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(request) => handle_request(context, &request),
+        Err(err) => json!({
+            "jsonrpc": "2.0",
+            "id": Value::Null,
+            "error": { "code": PARSE_ERROR, "message": err.to_string() },
+        }),
+    }
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|err| RpcError::new(INVALID_PARAMS, err.to_string()))
+}
+
+fn dispatch(context: &Context, method: &str, params: Value) -> Result<Value, RpcError> {
+    let ((tx_hash, index, args), level) = match method {
+        "run_script_level_code" => (parse_params::<CodeParams>(params)?, Level::Code),
+        "run_script_level_script" => {
+            let (tx_hash, index, args, script): (H256, u32, Vec<JsonBytes>, Script) =
+                parse_params(params)?;
+            ((tx_hash, index, args), Level::Script(script.into()))
+        }
+        "run_script_level_cell" => {
+            let (tx_hash, index, args, cell): (H256, u32, Vec<JsonBytes>, CellOutputWithData) =
+                parse_params(params)?;
+            let cell_output: packed::CellOutput = cell.cell_output.into();
+            // The cell runs as its type script, or as its lock if it has none.
+            let script = cell_output.type_().to_opt().unwrap_or(cell_output.lock());
+            let data = cell.hex_data.map(JsonBytes::into_bytes).unwrap_or_default();
+            let level = Level::Cell {
+                script,
+                cell: cell_output,
+                data,
+            };
+            ((tx_hash, index, args), level)
+        }
+        "run_script_level_transaction" => {
+            let (tx_hash, index, args, tx, script): (
+                H256,
+                u32,
+                Vec<JsonBytes>,
+                Transaction,
+                Script,
+            ) = parse_params(params)?;
+            let tx: TransactionView = packed::Transaction::from(tx).into_view();
+            let level = Level::Transaction {
+                script: script.into(),
+                tx,
+            };
+            ((tx_hash, index, args), level)
+        }
+        _ => {
+            return Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("method {} not found", method),
+            ))
+        }
+    };
+
+    let code_out_point = packed::OutPoint::new_builder()
+        .tx_hash(tx_hash.pack())
+        .index(index.pack())
+        .build();
+    let argv = args
+        .into_iter()
+        .map(|arg| arg.into_bytes().to_vec())
+        .collect::<Vec<_>>();
+    let execution = SSRIExecutor::new(context, &code_out_point)?.run(&argv, level)?;
+    Ok(json!({ "content": JsonBytes::from_bytes(execution.content) }))
+}
@@ -0,0 +1,70 @@
+use std::{path::PathBuf, process::exit};
+
+use ckb_testtool::ckb_types::{
+    packed::{Byte32, CellOutput},
+    prelude::*,
+    H256,
+};
+use ssri_server_local::{handle_body, load_fixture};
+use tiny_http::{Header, Method, Response, Server};
+
+const DEFAULT_LISTEN_ADDRESS: &str = "127.0.0.1:9090";
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(fixture_path) = args.next().map(PathBuf::from) else {
+        eprintln!("Usage: ssri-server-local <FIXTURE> [LISTEN_ADDRESS]");
+        exit(1);
+    };
+    let listen_address = args
+        .next()
+        .unwrap_or_else(|| DEFAULT_LISTEN_ADDRESS.to_string());
+
+    let context = load_fixture(&fixture_path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(1);
+    });
+    // Print the hashes needed to reference these cells from scripts and cell deps.
+    let mut cells = context.cells.iter().collect::<Vec<_>>();
+    cells.sort_by_key(|(out_point, _)| out_point.as_slice().to_vec());
+    for (out_point, (output, data)) in cells {
+        let type_hash = output
+            .type_()
+            .to_opt()
+            .map(|script| hex_hash(&script.calc_script_hash()))
+            .unwrap_or_else(|| String::from("-"));
+        println!(
+            "cell {}#{}: data_hash {}, type_hash {}",
+            hex_hash(&out_point.tx_hash()),
+            Unpack::<u32>::unpack(&out_point.index()),
+            hex_hash(&CellOutput::calc_data_hash(data)),
+            type_hash
+        );
+    }
+
+    let server = Server::http(&listen_address).unwrap_or_else(|err| {
+        eprintln!("Failed to listen on {}: {}", listen_address, err);
+        exit(1);
+    });
+    println!("Serving SSRI methods on http://{}", listen_address);
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    for mut request in server.incoming_requests() {
+        if *request.method() != Method::Post {
+            let _ = request.respond(Response::empty(405));
+            continue;
+        }
+        let mut body = Vec::new();
+        if request.as_reader().read_to_end(&mut body).is_err() {
+            let _ = request.respond(Response::empty(400));
+            continue;
+        }
+        let response = handle_body(&context, &body);
+        let _ = request
+            .respond(Response::from_string(response.to_string()).with_header(content_type.clone()));
+    }
+}
+
+fn hex_hash(hash: &Byte32) -> String {
+    format!("{:#x}", Unpack::<H256>::unpack(hash))
+}
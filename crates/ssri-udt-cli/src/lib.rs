@@ -0,0 +1,412 @@
+//! Offline transaction builders for ssri-udt.
+//!
+//! Live cells come from a ckb-testtool [`Context`], usually loaded from an
+//! ssri-server-local fixture. Outputs are produced by running the contract's
+//! own `SSRIUDT.create`, `UDT.mint` and `UDT.transfer` methods through
+//! `ssri-executor`; this crate only fills in capacities, the change cell and
+//! witness placeholders so the result is ready to sign.
+
+use std::{collections::BTreeMap, fmt};
+
+use ckb_testtool::{
+    ckb_types::{
+        bytes::Bytes,
+        core::{Capacity, DepType, ScriptHashType, TransactionBuilder, TransactionView},
+        packed::{Byte32, CellDep, CellInput, CellOutput, OutPoint, Script, Transaction},
+        prelude::*,
+    },
+    context::Context,
+};
+use serde_json::{json, Value};
+use ssri_executor::{ExecutionError, Level, SSRIExecutor};
+use ssri_udt_sdk::{argv, CreatedScripts, MetadataError, MetadataRecord, SSRIMetadata, UDTArgs};
+
+/// 0.001 CKB, enough for any transaction these builders produce at the
+/// minimum fee rate of 1000 shannons/KB.
+pub const DEFAULT_FEE: u64 = 100_000;
+
+#[derive(Debug)]
+pub enum CliError {
+    Execution(ExecutionError),
+    /// The out point is not among the live cells.
+    UnknownCell(OutPoint),
+    /// The code cell has no type script, so it cannot be referenced by type.
+    CodeCellWithoutType,
+    InvalidUdtCell(OutPoint),
+    InsufficientCapacity {
+        required: u64,
+        available: u64,
+    },
+    /// UDT inputs and outputs of a transfer must balance.
+    UnbalancedTransfer {
+        inputs: u128,
+        outputs: u128,
+    },
+    /// Checked before running `SSRIUDT.create`, which would reject it too.
+    InvalidMetadata(MetadataError),
+    Encoding,
+    Overflow,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Execution(err) => write!(f, "contract method failed: {}", err),
+            Self::UnknownCell(out_point) => write!(f, "cell {} is not a live cell", out_point),
+            Self::CodeCellWithoutType => write!(f, "code cell has no type script"),
+            Self::InvalidUdtCell(out_point) => {
+                write!(f, "cell {} does not hold a 16-byte UDT amount", out_point)
+            }
+            Self::InsufficientCapacity {
+                required,
+                available,
+            } => write!(
+                f,
+                "inputs hold {} shannons but {} are required",
+                available, required
+            ),
+            Self::UnbalancedTransfer { inputs, outputs } => write!(
+                f,
+                "UDT inputs sum to {} but transfer outputs sum to {}",
+                inputs, outputs
+            ),
+            Self::InvalidMetadata(err) => write!(f, "invalid metadata: {}", err),
+            Self::Encoding => write!(f, "failed to encode method arguments"),
+            Self::Overflow => write!(f, "amount or capacity overflow"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<ExecutionError> for CliError {
+    fn from(err: ExecutionError) -> Self {
+        Self::Execution(err)
+    }
+}
+
+/// Everything needed to balance and sign a transaction besides its UDT
+/// outputs.
+pub struct TxOptions {
+    pub change_lock: Script,
+    pub fee: u64,
+    /// Deps for the input locks, e.g. the secp256k1 dep group.
+    pub cell_deps: Vec<CellDep>,
+}
+
+pub struct UdtTxBuilder<'a> {
+    context: &'a Context,
+    code: OutPoint,
+    hash_type: ScriptHashType,
+}
+
+impl<'a> UdtTxBuilder<'a> {
+    /// `hash_type` decides whether UDT type scripts reference the code cell by
+    /// data hash or by type hash.
+    pub fn new(
+        context: &'a Context,
+        code: OutPoint,
+        hash_type: ScriptHashType,
+    ) -> Result<Self, CliError> {
+        if context.get_cell(&code).is_none() {
+            return Err(CliError::UnknownCell(code));
+        }
+        Ok(Self {
+            context,
+            code,
+            hash_type,
+        })
+    }
+
+    /// The UDT type script whose args are the Type ID args of its metadata cell.
+    pub fn udt_type_script(&self, args: &[u8]) -> Result<Script, CliError> {
+        let (code_cell, code) = self.live_cell(&self.code)?;
+        let code_hash = match self.hash_type {
+            ScriptHashType::Type => code_cell
+                .type_()
+                .to_opt()
+                .ok_or(CliError::CodeCellWithoutType)?
+                .calc_script_hash(),
+            _ => CellOutput::calc_data_hash(&code),
+        };
+        Ok(Script::new_builder()
+            .code_hash(code_hash)
+            .hash_type(self.hash_type.into())
+            .args(args.pack())
+            .build())
+    }
+
+    /// With `immutable` the metadata cell has no type script and can never
+    /// change; see `immutable_udt_type_script` for the UDT it defines.
+    pub fn create(
+        &self,
+        inputs: &[OutPoint],
+        owner_lock: &Script,
+        metadata: &SSRIMetadata,
+        immutable: bool,
+        options: &TxOptions,
+    ) -> Result<TransactionView, CliError> {
+        metadata.validate().map_err(CliError::InvalidMetadata)?;
+        let held_tx = self.held_tx(inputs, options.cell_deps.clone())?;
+        let argv = if immutable {
+            argv::create_immutable(&held_tx.data(), owner_lock, metadata)
+        } else {
+            argv::create(&held_tx.data(), owner_lock, metadata)
+        }
+        .map_err(|_| CliError::Encoding)?;
+        let tx = self.run(&argv, Level::Code)?;
+        self.complete(tx, options)
+    }
+
+    pub fn mint(
+        &self,
+        udt: &Script,
+        inputs: &[OutPoint],
+        to: &[(Script, u128)],
+        options: &TxOptions,
+    ) -> Result<TransactionView, CliError> {
+        let held_tx = self.held_tx(inputs, self.cell_deps_with_code(options))?;
+        let (locks, amounts): (Vec<_>, Vec<_>) = to.iter().cloned().unzip();
+        let argv =
+            argv::mint(Some(&held_tx.data()), &locks, &amounts).map_err(|_| CliError::Encoding)?;
+        let tx = self.run(&argv, Level::Script(udt.clone()))?;
+        self.complete(tx, options)
+    }
+
+    pub fn transfer(
+        &self,
+        udt: &Script,
+        inputs: &[OutPoint],
+        to: &[(Script, u128)],
+        options: &TxOptions,
+    ) -> Result<TransactionView, CliError> {
+        let inputs_amount = self.udt_amount(udt, inputs)?;
+        let outputs_amount = to
+            .iter()
+            .try_fold(0u128, |sum, (_, amount)| sum.checked_add(*amount))
+            .ok_or(CliError::Overflow)?;
+        if inputs_amount != outputs_amount {
+            return Err(CliError::UnbalancedTransfer {
+                inputs: inputs_amount,
+                outputs: outputs_amount,
+            });
+        }
+
+        let held_tx = self.held_tx(inputs, self.cell_deps_with_code(options))?;
+        let (locks, amounts): (Vec<_>, Vec<_>) = to.iter().cloned().unzip();
+        let argv = argv::transfer(Some(&held_tx.data()), &locks, &amounts)
+            .map_err(|_| CliError::Encoding)?;
+        let tx = self.run(&argv, Level::Script(udt.clone()))?;
+        self.complete(tx, options)
+    }
+
+    /// Type script of the UDT defined by a `create` transaction, as returned by
+    /// `SSRIUDT.created_scripts`.
+    pub fn created_udt_type_script(&self, tx: &TransactionView) -> Result<Script, CliError> {
+        let any_udt = self.udt_type_script(&[])?;
+        let execution = SSRIExecutor::new(self.context, &self.code)?
+            .run_script_level_script(&argv::created_scripts(&tx.data()), &any_udt)?;
+        let scripts = CreatedScripts::decode(&execution.content).map_err(|_| CliError::Encoding)?;
+        Ok(scripts.into_scripts().1)
+    }
+
+    /// Type script of the UDT defined by an immutable `create`, whose args
+    /// commit to `owner_lock` and the metadata cell data.
+    pub fn immutable_udt_type_script(
+        &self,
+        owner_lock: &Script,
+        metadata: &SSRIMetadata,
+    ) -> Result<Script, CliError> {
+        let data = metadata.encode().map_err(|_| CliError::Encoding)?;
+        let args = UDTArgs::immutable(owner_lock.calc_script_hash().unpack(), &data);
+        self.udt_type_script(&args.to_vec())
+    }
+
+    /// Metadata of the UDT and the balance of every lock among the live cells.
+    pub fn inspect(&self, udt: &Script) -> Result<Value, CliError> {
+        let executor = SSRIExecutor::new(self.context, &self.code)?;
+        let execution = executor.run_script_level_script(&argv::metadata(), udt)?;
+        let record = MetadataRecord::decode(&execution.content).map_err(|_| CliError::Encoding)?;
+        let metadata = record.metadata;
+        // Only an icon in a cell of its own needs another run.
+        let icon = match metadata.icon_ref {
+            None => metadata.icon,
+            Some(_) => {
+                let execution = executor.run_script_level_script(&argv::icon(), udt)?;
+                String::from_utf8_lossy(&execution.content).into_owned()
+            }
+        };
+
+        let mut balances = BTreeMap::<Vec<u8>, (Script, u128)>::new();
+        let mut total = 0u128;
+        for (out_point, (output, data)) in self.context.cells.iter() {
+            if output.type_().to_opt().as_ref() != Some(udt) {
+                continue;
+            }
+            let amount = parse_amount(out_point, data)?;
+            let lock = output.lock();
+            let entry = balances
+                .entry(lock.calc_script_hash().as_slice().to_vec())
+                .or_insert((lock, 0));
+            entry.1 = entry.1.checked_add(amount).ok_or(CliError::Overflow)?;
+            total = total.checked_add(amount).ok_or(CliError::Overflow)?;
+        }
+        let balances = balances
+            .into_values()
+            .map(|(lock, amount)| {
+                json!({
+                    "lock_hash": hex_byte32(&lock.calc_script_hash()),
+                    "lock": ckb_jsonrpc_types::Script::from(lock),
+                    "amount": amount.to_string(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(json!({
+            "type_script": ckb_jsonrpc_types::Script::from(udt.clone()),
+            "type_hash": hex_byte32(&udt.calc_script_hash()),
+            "name": metadata.name,
+            "symbol": metadata.symbol,
+            "decimals": metadata.decimals,
+            "icon": icon,
+            "balances": balances,
+            "total": total.to_string(),
+        }))
+    }
+
+    fn live_cell(&self, out_point: &OutPoint) -> Result<(CellOutput, Bytes), CliError> {
+        self.context
+            .get_cell(out_point)
+            .ok_or_else(|| CliError::UnknownCell(out_point.clone()))
+    }
+
+    fn cell_deps_with_code(&self, options: &TxOptions) -> Vec<CellDep> {
+        let code_dep = CellDep::new_builder()
+            .out_point(self.code.clone())
+            .dep_type(DepType::Code.into())
+            .build();
+        std::iter::once(code_dep)
+            .chain(options.cell_deps.iter().cloned())
+            .collect()
+    }
+
+    fn held_tx(
+        &self,
+        inputs: &[OutPoint],
+        cell_deps: Vec<CellDep>,
+    ) -> Result<TransactionView, CliError> {
+        for input in inputs {
+            self.live_cell(input)?;
+        }
+        Ok(TransactionBuilder::default()
+            .inputs(inputs.iter().map(|input| CellInput::new(input.clone(), 0)))
+            .cell_deps(cell_deps)
+            .build())
+    }
+
+    fn udt_amount(&self, udt: &Script, inputs: &[OutPoint]) -> Result<u128, CliError> {
+        let mut sum = 0u128;
+        for input in inputs {
+            let (output, data) = self.live_cell(input)?;
+            if output.type_().to_opt().as_ref() == Some(udt) {
+                sum = sum
+                    .checked_add(parse_amount(input, &data)?)
+                    .ok_or(CliError::Overflow)?;
+            }
+        }
+        Ok(sum)
+    }
+
+    fn run(&self, argv: &[Vec<u8>], level: Level) -> Result<TransactionView, CliError> {
+        let execution = SSRIExecutor::new(self.context, &self.code)?.run(argv, level)?;
+        Transaction::from_slice(&execution.content)
+            .map(IntoTransactionView::into_view)
+            .map_err(|_| CliError::Encoding)
+    }
+
+    /// Gives zero-capacity outputs their occupied capacity, appends the change
+    /// cell and pads witnesses to one per input.
+    fn complete(
+        &self,
+        tx: TransactionView,
+        options: &TxOptions,
+    ) -> Result<TransactionView, CliError> {
+        let mut outputs = Vec::with_capacity(tx.outputs().len() + 1);
+        let mut outputs_capacity = 0u64;
+        for (output, data) in tx.outputs_with_data_iter() {
+            let output = if Unpack::<u64>::unpack(&output.capacity()) == 0 {
+                let occupied = occupied_capacity(&output, data.len())?;
+                output.as_builder().capacity(occupied.pack()).build()
+            } else {
+                output
+            };
+            outputs_capacity = outputs_capacity
+                .checked_add(output.capacity().unpack())
+                .ok_or(CliError::Overflow)?;
+            outputs.push(output);
+        }
+
+        let mut available = 0u64;
+        for input in tx.input_pts_iter() {
+            let (cell, _) = self.live_cell(&input)?;
+            available = available
+                .checked_add(cell.capacity().unpack())
+                .ok_or(CliError::Overflow)?;
+        }
+        let change = CellOutput::new_builder()
+            .lock(options.change_lock.clone())
+            .build();
+        let required = outputs_capacity
+            .checked_add(options.fee)
+            .and_then(|sum| sum.checked_add(occupied_capacity(&change, 0).ok()?))
+            .ok_or(CliError::Overflow)?;
+        if available < required {
+            return Err(CliError::InsufficientCapacity {
+                required,
+                available,
+            });
+        }
+        let change_capacity = available - outputs_capacity - options.fee;
+        outputs.push(change.as_builder().capacity(change_capacity.pack()).build());
+
+        let mut witnesses = tx.witnesses().into_iter().collect::<Vec<_>>();
+        witnesses.resize(witnesses.len().max(tx.inputs().len()), Bytes::new().pack());
+        Ok(tx
+            .as_advanced_builder()
+            .set_outputs(outputs)
+            .output_data(Bytes::new().pack())
+            .set_witnesses(witnesses)
+            .build())
+    }
+}
+
+/// The ckb-cli transaction file format (`ckb-cli tx sign-inputs --tx-file`).
+/// `transaction` is in CKB JSON-RPC form, which CCC also reads.
+pub fn to_ckb_cli_json(tx: &TransactionView) -> Value {
+    json!({
+        "transaction": ckb_jsonrpc_types::Transaction::from(tx.data()),
+        "multisig_configs": {},
+        "signatures": {},
+    })
+}
+
+fn parse_amount(out_point: &OutPoint, data: &[u8]) -> Result<u128, CliError> {
+    let amount: [u8; 16] = data
+        .try_into()
+        .map_err(|_| CliError::InvalidUdtCell(out_point.clone()))?;
+    Ok(u128::from_le_bytes(amount))
+}
+
+fn occupied_capacity(output: &CellOutput, data_len: usize) -> Result<u64, CliError> {
+    Capacity::bytes(data_len)
+        .and_then(|data_capacity| output.occupied_capacity(data_capacity))
+        .map(Capacity::as_u64)
+        .map_err(|_| CliError::Overflow)
+}
+
+fn hex_byte32(hash: &Byte32) -> String {
+    format!(
+        "{:#x}",
+        Unpack::<ckb_testtool::ckb_types::H256>::unpack(hash)
+    )
+}
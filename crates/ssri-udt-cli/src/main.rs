@@ -0,0 +1,302 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+use ckb_testtool::ckb_types::{
+    bytes::Bytes,
+    core::{DepType, ScriptHashType, TransactionView},
+    packed::{CellDep, OutPoint, Script},
+    prelude::*,
+    H256,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ssri_server_local::load_fixture;
+use ssri_udt_cli::{to_ckb_cli_json, CliError, TxOptions, UdtTxBuilder, DEFAULT_FEE};
+use ssri_udt_sdk::{decode, SSRIMetadata};
+
+#[derive(Parser)]
+#[command(about = "Build ssri-udt transactions offline from a file of live cells")]
+struct Cli {
+    /// Live cells, in the ssri-server-local fixture format (JSON or TOML).
+    #[arg(long)]
+    cells: PathBuf,
+    /// Out point of the ssri-udt code cell, as <TX_HASH>:<INDEX>.
+    #[arg(long, value_parser = parse_out_point)]
+    code: OutPoint,
+    /// How UDT type scripts reference the code cell.
+    #[arg(long, value_enum, default_value_t = HashType::Data1)]
+    hash_type: HashType,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum HashType {
+    Data1,
+    Data2,
+    Type,
+}
+
+impl From<HashType> for ScriptHashType {
+    fn from(hash_type: HashType) -> Self {
+        match hash_type {
+            HashType::Data1 => ScriptHashType::Data1,
+            HashType::Data2 => ScriptHashType::Data2,
+            HashType::Type => ScriptHashType::Type,
+        }
+    }
+}
+
+#[derive(Args)]
+struct TxArgs {
+    /// Input out point, as <TX_HASH>:<INDEX>. Repeat for more inputs.
+    #[arg(long = "input", value_parser = parse_out_point, required = true)]
+    inputs: Vec<OutPoint>,
+    /// Lock of the CKB change cell, as script JSON or @<FILE>.
+    #[arg(long, value_parser = parse_script)]
+    change_lock: Script,
+    /// Fee in shannons, taken from the change cell.
+    #[arg(long, default_value_t = DEFAULT_FEE)]
+    fee: u64,
+    /// Extra code cell dep for the input locks. Repeatable.
+    #[arg(long = "cell-dep", value_parser = parse_out_point)]
+    cell_deps: Vec<OutPoint>,
+    /// Extra dep group cell dep, e.g. secp256k1. Repeatable.
+    #[arg(long = "dep-group", value_parser = parse_out_point)]
+    dep_groups: Vec<OutPoint>,
+    /// Write the transaction here instead of stdout.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+impl TxArgs {
+    fn options(&self) -> TxOptions {
+        let cell_dep = |out_point: &OutPoint, dep_type: DepType| {
+            CellDep::new_builder()
+                .out_point(out_point.clone())
+                .dep_type(dep_type.into())
+                .build()
+        };
+        let cell_deps = self
+            .cell_deps
+            .iter()
+            .map(|out_point| cell_dep(out_point, DepType::Code))
+            .chain(
+                self.dep_groups
+                    .iter()
+                    .map(|out_point| cell_dep(out_point, DepType::DepGroup)),
+            )
+            .collect();
+        TxOptions {
+            change_lock: self.change_lock.clone(),
+            fee: self.fee,
+            cell_deps,
+        }
+    }
+}
+
+#[derive(Args)]
+struct ToArgs {
+    /// Args of the UDT type script: the Type ID args of its metadata cell, in hex.
+    #[arg(long, value_parser = parse_bytes)]
+    udt_args: Bytes,
+    /// Receiver lock, as script JSON or @<FILE>. Pairs with --amount by position.
+    #[arg(long, value_parser = parse_script, required = true)]
+    to: Vec<Script>,
+    /// Amount for the receiver at the same position, in the smallest unit.
+    #[arg(long, required = true)]
+    amount: Vec<u128>,
+}
+
+impl ToArgs {
+    fn pairs(&self) -> Result<Vec<(Script, u128)>, String> {
+        if self.to.len() != self.amount.len() {
+            return Err(format!(
+                "{} --to given for {} --amount",
+                self.to.len(),
+                self.amount.len()
+            ));
+        }
+        Ok(self.to.iter().cloned().zip(self.amount.clone()).collect())
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the metadata cell of a new UDT (SSRIUDT.create): a Type ID cell,
+    /// or with --immutable a cell the UDT args commit to.
+    Create {
+        #[command(flatten)]
+        tx: TxArgs,
+        /// Owner lock of the metadata cell; inputs with it may mint.
+        #[arg(long, value_parser = parse_script)]
+        owner_lock: Script,
+        /// Metadata as JSON with the fields of `SSRIMetadata`, read from this
+        /// file in place of --name, --symbol, --decimals and --icon.
+        #[arg(long, conflicts_with_all = ["name", "symbol", "decimals", "icon"])]
+        metadata: Option<PathBuf>,
+        #[arg(long, required_unless_present = "metadata")]
+        name: Option<String>,
+        #[arg(long, required_unless_present = "metadata")]
+        symbol: Option<String>,
+        #[arg(long, required_unless_present = "metadata")]
+        decimals: Option<u8>,
+        #[arg(long)]
+        icon: Option<String>,
+        /// Commit the UDT args to the metadata, which can then never change.
+        #[arg(long)]
+        immutable: bool,
+    },
+    /// Mint to receivers (UDT.mint). An input must use the owner lock.
+    Mint {
+        #[command(flatten)]
+        tx: TxArgs,
+        #[command(flatten)]
+        to: ToArgs,
+    },
+    /// Move UDT from the inputs to receivers (UDT.transfer). Amounts must add
+    /// up to the UDT inputs, so include your own change as a receiver.
+    Transfer {
+        #[command(flatten)]
+        tx: TxArgs,
+        #[command(flatten)]
+        to: ToArgs,
+    },
+    /// Print metadata and per-lock balances of a UDT as JSON.
+    Inspect {
+        #[arg(long, value_parser = parse_bytes)]
+        udt_args: Bytes,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("Error: {}", err);
+        exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let context = load_fixture(&cli.cells).map_err(|err| err.to_string())?;
+    let builder = UdtTxBuilder::new(&context, cli.code, cli.hash_type.into())
+        .map_err(|err| err.to_string())?;
+
+    let (tx, tx_args) = match &cli.command {
+        Command::Create {
+            tx,
+            owner_lock,
+            metadata,
+            name,
+            symbol,
+            decimals,
+            icon,
+            immutable,
+        } => {
+            // Clap requires either --metadata or all of the fields.
+            let metadata = match metadata {
+                Some(path) => read_metadata(path)?,
+                None => SSRIMetadata {
+                    name: name.clone().unwrap_or_default(),
+                    symbol: symbol.clone().unwrap_or_default(),
+                    decimals: decimals.unwrap_or_default(),
+                    icon: icon.clone().unwrap_or_default(),
+                    ..Default::default()
+                },
+            };
+            let created =
+                builder.create(&tx.inputs, owner_lock, &metadata, *immutable, &tx.options());
+            if let Ok(created) = &created {
+                let udt = if *immutable {
+                    builder.immutable_udt_type_script(owner_lock, &metadata)
+                } else {
+                    builder.created_udt_type_script(created)
+                }
+                .map_err(|e| e.to_string())?;
+                eprintln!("UDT args (--udt-args): 0x{:x}", udt.args().raw_data());
+            }
+            (created, tx)
+        }
+        Command::Mint { tx, to } => {
+            let udt = builder
+                .udt_type_script(&to.udt_args)
+                .map_err(|e| e.to_string())?;
+            let minted = builder.mint(&udt, &tx.inputs, &to.pairs()?, &tx.options());
+            (minted, tx)
+        }
+        Command::Transfer { tx, to } => {
+            let udt = builder
+                .udt_type_script(&to.udt_args)
+                .map_err(|e| e.to_string())?;
+            let transferred = builder.transfer(&udt, &tx.inputs, &to.pairs()?, &tx.options());
+            (transferred, tx)
+        }
+        Command::Inspect { udt_args } => {
+            let udt = builder
+                .udt_type_script(udt_args)
+                .map_err(|e| e.to_string())?;
+            let report = builder.inspect(&udt).map_err(|e| e.to_string())?;
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            return Ok(());
+        }
+    };
+    write_tx(tx, tx_args.output.as_ref())
+}
+
+fn write_tx(tx: Result<TransactionView, CliError>, output: Option<&PathBuf>) -> Result<(), String> {
+    let tx = tx.map_err(|err| err.to_string())?;
+    let json = serde_json::to_string_pretty(&to_ckb_cli_json(&tx)).unwrap();
+    match output {
+        Some(path) => fs::write(path, json + "\n").map_err(|err| err.to_string())?,
+        None => println!("{}", json),
+    }
+    eprintln!(
+        "Transaction hash: {:#x}",
+        Unpack::<H256>::unpack(&tx.hash())
+    );
+    Ok(())
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    decode::decode_hex(hex.as_bytes())
+        .map_err(|_| String::from("expected an even number of hex digits 0-9, a-f or A-F"))
+}
+
+fn parse_bytes(hex: &str) -> Result<Bytes, String> {
+    parse_hex(hex).map(Bytes::from)
+}
+
+fn parse_out_point(value: &str) -> Result<OutPoint, String> {
+    let (tx_hash, index) = value
+        .split_once(':')
+        .ok_or_else(|| String::from("expected <TX_HASH>:<INDEX>"))?;
+    let tx_hash: [u8; 32] = parse_hex(tx_hash)?
+        .try_into()
+        .map_err(|_| String::from("tx hash must be 32 bytes"))?;
+    let index: u32 = index.parse().map_err(|err| format!("index: {}", err))?;
+    Ok(OutPoint::new_builder()
+        .tx_hash(tx_hash.pack())
+        .index(index.pack())
+        .build())
+}
+
+/// `SSRIMetadata` as JSON, read from the file at `path`. Fields after
+/// `icon` may be left out.
+fn read_metadata(path: &Path) -> Result<SSRIMetadata, String> {
+    let json = fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    serde_json::from_str(&json).map_err(|err| err.to_string())
+}
+
+/// Script JSON as in CKB JSON-RPC, inline or read from `@<FILE>`.
+fn parse_script(value: &str) -> Result<Script, String> {
+    let json = match value.strip_prefix('@') {
+        Some(path) => fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?,
+        None => value.to_string(),
+    };
+    let script: ckb_jsonrpc_types::Script =
+        serde_json::from_str(&json).map_err(|err| err.to_string())?;
+    Ok(script.into())
+}
@@ -0,0 +1,261 @@
+//! Typed argv builders for every SSRI method exposed by `program_entry_wrap`.
+//!
+//! Each builder returns the raw arguments in order, with the method path as
+//! `argv[0]`. Use [`to_hex`] to render them for the ssri-server JSON-RPC.
+
+use alloc::{string::String, vec, vec::Vec};
+use ckb_hash::blake2b_256;
+use ckb_ssri_std::prelude::encode_u64_vector;
+use ckb_std::ckb_types::{
+    packed::{Script, Transaction},
+    prelude::*,
+};
+use core::fmt::Write;
+
+use crate::{codec::encode_amount_vec, SSRIMetadata, ScriptLikeVec};
+
+pub const SSRI_VERSION: &str = "SSRI.version";
+pub const SSRI_GET_METHODS: &str = "SSRI.get_methods";
+pub const SSRI_HAS_METHODS: &str = "SSRI.has_methods";
+pub const UDT_NAME: &str = "UDT.name";
+pub const UDT_SYMBOL: &str = "UDT.symbol";
+pub const UDT_DECIMALS: &str = "UDT.decimals";
+pub const UDT_ICON: &str = "UDT.icon";
+pub const UDT_TRANSFER: &str = "UDT.transfer";
+pub const UDT_MINT: &str = "UDT.mint";
+pub const SSRIUDT_CREATE: &str = "SSRIUDT.create";
+pub const SSRIUDT_MIGRATE_METADATA: &str = "SSRIUDT.migrate_metadata";
+pub const SSRIUDT_CREATE_AND_MINT: &str = "SSRIUDT.create_and_mint";
+pub const SSRIUDT_CREATED_SCRIPTS: &str = "SSRIUDT.created_scripts";
+pub const SSRIUDT_AIRDROP: &str = "SSRIUDT.airdrop";
+pub const SSRIUDT_DESCRIBE_TRANSACTION: &str = "SSRIUDT.describe_transaction";
+pub const SSRIUDT_VERIFY: &str = "SSRIUDT.verify";
+pub const SSRIUDT_METADATA: &str = "SSRIUDT.metadata";
+pub const SSRIUDT_DESCRIPTION: &str = "SSRIUDT.description";
+pub const SSRIUDT_WEBSITE: &str = "SSRIUDT.website";
+pub const SSRIUDT_SOCIAL_LINKS: &str = "SSRIUDT.social_links";
+pub const SSRIUDT_CATEGORY: &str = "SSRIUDT.category";
+pub const SSRIUDT_EXTRA: &str = "SSRIUDT.extra";
+pub const SSRIUDT_NAME_LOCALIZED: &str = "SSRIUDT.name_localized";
+pub const SSRIUDT_DESCRIPTION_LOCALIZED: &str = "SSRIUDT.description_localized";
+
+pub type Argv = Vec<Vec<u8>>;
+
+/// The first 8 bytes of `blake2b_256(name)`, read as little-endian `u64`.
+pub fn method_path(name: &str) -> u64 {
+    u64::from_le_bytes(blake2b_256(name)[0..8].try_into().unwrap())
+}
+
+fn path_only(name: &str) -> Argv {
+    vec![method_path(name).to_le_bytes().to_vec()]
+}
+
+fn optional_tx(tx: Option<&Transaction>) -> Vec<u8> {
+    tx.map(|tx| tx.as_slice().to_vec()).unwrap_or_default()
+}
+
+pub fn version() -> Argv {
+    path_only(SSRI_VERSION)
+}
+
+pub fn get_methods(offset: u64, limit: u64) -> Argv {
+    vec![
+        method_path(SSRI_GET_METHODS).to_le_bytes().to_vec(),
+        offset.to_le_bytes().to_vec(),
+        limit.to_le_bytes().to_vec(),
+    ]
+}
+
+pub fn has_methods(names: &[&str]) -> Argv {
+    let paths = names
+        .iter()
+        .map(|name| method_path(name))
+        .collect::<Vec<_>>();
+    vec![
+        method_path(SSRI_HAS_METHODS).to_le_bytes().to_vec(),
+        encode_u64_vector(paths),
+    ]
+}
+
+pub fn name() -> Argv {
+    path_only(UDT_NAME)
+}
+
+pub fn symbol() -> Argv {
+    path_only(UDT_SYMBOL)
+}
+
+pub fn decimals() -> Argv {
+    path_only(UDT_DECIMALS)
+}
+
+pub fn icon() -> Argv {
+    path_only(UDT_ICON)
+}
+
+pub fn transfer(
+    tx: Option<&Transaction>,
+    to_lock_vec: &[Script],
+    to_amount_vec: &[u128],
+) -> Result<Argv, serde_molecule::Error> {
+    Ok(vec![
+        method_path(UDT_TRANSFER).to_le_bytes().to_vec(),
+        optional_tx(tx),
+        ScriptLikeVec::from_scripts(to_lock_vec).encode()?,
+        encode_amount_vec(to_amount_vec),
+    ])
+}
+
+pub fn mint(
+    tx: Option<&Transaction>,
+    to_lock_vec: &[Script],
+    to_amount_vec: &[u128],
+) -> Result<Argv, serde_molecule::Error> {
+    Ok(vec![
+        method_path(UDT_MINT).to_le_bytes().to_vec(),
+        optional_tx(tx),
+        ScriptLikeVec::from_scripts(to_lock_vec).encode()?,
+        encode_amount_vec(to_amount_vec),
+    ])
+}
+
+pub fn metadata() -> Argv {
+    path_only(SSRIUDT_METADATA)
+}
+
+pub fn description() -> Argv {
+    path_only(SSRIUDT_DESCRIPTION)
+}
+
+pub fn website() -> Argv {
+    path_only(SSRIUDT_WEBSITE)
+}
+
+pub fn social_links() -> Argv {
+    path_only(SSRIUDT_SOCIAL_LINKS)
+}
+
+pub fn category() -> Argv {
+    path_only(SSRIUDT_CATEGORY)
+}
+
+pub fn extra(key: &str) -> Argv {
+    vec![
+        method_path(SSRIUDT_EXTRA).to_le_bytes().to_vec(),
+        key.as_bytes().to_vec(),
+    ]
+}
+
+pub fn name_localized(locale: &str) -> Argv {
+    vec![
+        method_path(SSRIUDT_NAME_LOCALIZED).to_le_bytes().to_vec(),
+        locale.as_bytes().to_vec(),
+    ]
+}
+
+pub fn description_localized(locale: &str) -> Argv {
+    vec![
+        method_path(SSRIUDT_DESCRIPTION_LOCALIZED)
+            .to_le_bytes()
+            .to_vec(),
+        locale.as_bytes().to_vec(),
+    ]
+}
+
+pub fn migrate_metadata(tx: Option<&Transaction>) -> Argv {
+    vec![
+        method_path(SSRIUDT_MIGRATE_METADATA).to_le_bytes().to_vec(),
+        optional_tx(tx),
+    ]
+}
+
+/// Run it at script level with any ssri-udt type script; only its code hash
+/// and hash type are used.
+pub fn create_and_mint(
+    tx: &Transaction,
+    owner_lock: &Script,
+    metadata: &SSRIMetadata,
+    to_lock_vec: &[Script],
+    to_amount_vec: &[u128],
+) -> Result<Argv, serde_molecule::Error> {
+    let mut argv = create(tx, owner_lock, metadata)?;
+    argv[0] = method_path(SSRIUDT_CREATE_AND_MINT).to_le_bytes().to_vec();
+    argv.push(ScriptLikeVec::from_scripts(to_lock_vec).encode()?);
+    argv.push(encode_amount_vec(to_amount_vec));
+    Ok(argv)
+}
+
+/// Run it at script level with any ssri-udt type script; only its code hash
+/// and hash type are used.
+pub fn created_scripts(tx: &Transaction) -> Argv {
+    vec![
+        method_path(SSRIUDT_CREATED_SCRIPTS).to_le_bytes().to_vec(),
+        tx.as_slice().to_vec(),
+    ]
+}
+
+pub fn airdrop(
+    tx: Option<&Transaction>,
+    to_lock_vec: &[Script],
+    to_amount_vec: &[u128],
+    max_outputs: u32,
+) -> Result<Argv, serde_molecule::Error> {
+    Ok(vec![
+        method_path(SSRIUDT_AIRDROP).to_le_bytes().to_vec(),
+        optional_tx(tx),
+        ScriptLikeVec::from_scripts(to_lock_vec).encode()?,
+        encode_amount_vec(to_amount_vec),
+        max_outputs.to_le_bytes().to_vec(),
+    ])
+}
+
+pub fn create(
+    tx: &Transaction,
+    owner_lock: &Script,
+    metadata: &SSRIMetadata,
+) -> Result<Argv, serde_molecule::Error> {
+    Ok(vec![
+        method_path(SSRIUDT_CREATE).to_le_bytes().to_vec(),
+        tx.as_slice().to_vec(),
+        owner_lock.as_slice().to_vec(),
+        metadata.encode()?,
+    ])
+}
+
+/// `SSRIUDT.create` for a token whose args commit to the metadata, see
+/// [`crate::UDTArgs::Immutable`]. The metadata cell gets no type script.
+pub fn create_immutable(
+    tx: &Transaction,
+    owner_lock: &Script,
+    metadata: &SSRIMetadata,
+) -> Result<Argv, serde_molecule::Error> {
+    let mut argv = create(tx, owner_lock, metadata)?;
+    argv.push(vec![1]);
+    Ok(argv)
+}
+
+/// Takes no arguments; run it at transaction level with the transaction to
+/// describe and the UDT type script.
+pub fn describe_transaction() -> Argv {
+    path_only(SSRIUDT_DESCRIBE_TRANSACTION)
+}
+
+/// Takes no arguments; run it at transaction level with the transaction to
+/// verify and the UDT type script.
+pub fn verify() -> Argv {
+    path_only(SSRIUDT_VERIFY)
+}
+
+/// Renders argv as `0x`-prefixed hex strings, as accepted by ssri-server.
+pub fn to_hex(argv: &[Vec<u8>]) -> Vec<String> {
+    argv.iter()
+        .map(|arg| {
+            let mut hex = String::with_capacity(2 + arg.len() * 2);
+            hex.push_str("0x");
+            for byte in arg {
+                write!(hex, "{:02x}", byte).unwrap();
+            }
+            hex
+        })
+        .collect()
+}
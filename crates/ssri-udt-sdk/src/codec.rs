@@ -0,0 +1,60 @@
+use alloc::vec::Vec;
+use ckb_ssri_std::public_module_traits::udt::ScriptLike;
+use ckb_std::ckb_types::{packed::Script, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_molecule::{dynvec_serde, from_slice, to_vec};
+
+/// Molecule `dynvec` of scripts, used for the `to_lock_vec` method arguments.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct ScriptLikeVec {
+    #[serde(with = "dynvec_serde")]
+    pub scripts: Vec<ScriptLike>,
+}
+
+pub(crate) fn to_script_like(script: &Script) -> ScriptLike {
+    ScriptLike {
+        code_hash: script.code_hash().unpack(),
+        hash_type: script.hash_type().into(),
+        args: script.args().raw_data().to_vec(),
+    }
+}
+
+pub(crate) fn from_script_like(script: ScriptLike) -> Script {
+    Script::new_builder()
+        .code_hash(script.code_hash.pack())
+        .hash_type(script.hash_type.into())
+        .args(script.args.pack())
+        .build()
+}
+
+impl ScriptLikeVec {
+    pub fn from_scripts(scripts: &[Script]) -> Self {
+        let scripts = scripts.iter().map(to_script_like).collect();
+        Self { scripts }
+    }
+
+    pub fn into_scripts(self) -> Vec<Script> {
+        self.scripts.into_iter().map(from_script_like).collect()
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, serde_molecule::Error> {
+        to_vec(self, false)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, serde_molecule::Error> {
+        from_slice(data, false)
+    }
+}
+
+/// Encodes amounts as a molecule `fixvec` of little-endian `u128`.
+pub fn encode_amount_vec(amounts: &[u128]) -> Vec<u8> {
+    // Fixed-size items never fail to serialize.
+    to_vec(amounts, false).expect("fixvec of u128")
+}
+
+/// Decodes a molecule `fixvec` of little-endian `u128`, checking the item count
+/// against the actual length.
+pub fn decode_amount_vec(data: &[u8]) -> Result<Vec<u128>, serde_molecule::Error> {
+    from_slice(data, false)
+}
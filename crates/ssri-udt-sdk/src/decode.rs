@@ -0,0 +1,107 @@
+//! Decoders for the raw SSRI method arguments, the inverse of [`crate::argv`].
+//!
+//! `program_entry_wrap` hands every argument it reads to one of these, so the
+//! whole decoding surface can be exercised on the host. None of them panic:
+//! malformed input always comes back as an [`ArgError`].
+
+use alloc::vec::Vec;
+use ckb_std::ckb_types::{
+    packed::{Script, Transaction},
+    prelude::*,
+};
+
+use crate::{codec::decode_amount_vec, SSRIMetadata, ScriptLikeVec};
+
+/// Why an SSRI argument could not be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgError {
+    /// The argument is missing or is not valid for the method.
+    Invalid,
+    /// The argument is not an even-length hex string.
+    Encoding,
+    /// The hex decodes to bytes that are not the expected molecule type.
+    Molecule,
+}
+
+/// The `index`th argument, or [`ArgError::Invalid`] when there are fewer.
+pub fn arg<T: AsRef<[u8]>>(argv: &[T], index: usize) -> Result<&[u8], ArgError> {
+    argv.get(index).map(AsRef::as_ref).ok_or(ArgError::Invalid)
+}
+
+fn hex_nibble(c: u8) -> Result<u8, ArgError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(ArgError::Encoding),
+    }
+}
+
+/// Decodes a hex argument. Unlike `ckb_std::high_level::decode_hex`, bytes
+/// that are not UTF-8 are an encoding error rather than a panic.
+pub fn decode_hex(data: &[u8]) -> Result<Vec<u8>, ArgError> {
+    if !data.len().is_multiple_of(2) {
+        return Err(ArgError::Encoding);
+    }
+    data.chunks_exact(2)
+        .map(|pair| Ok(hex_nibble(pair[0])? << 4 | hex_nibble(pair[1])?))
+        .collect()
+}
+
+pub fn decode_tx(data: &[u8]) -> Result<Transaction, ArgError> {
+    Transaction::from_compatible_slice(&decode_hex(data)?).map_err(|_| ArgError::Molecule)
+}
+
+/// A transaction argument that may be left empty to start a new one.
+pub fn decode_optional_tx(data: &[u8]) -> Result<Option<Transaction>, ArgError> {
+    if data.is_empty() {
+        return Ok(None);
+    }
+    decode_tx(data).map(Some)
+}
+
+pub fn decode_script(data: &[u8]) -> Result<Script, ArgError> {
+    Script::from_compatible_slice(&decode_hex(data)?).map_err(|_| ArgError::Molecule)
+}
+
+pub fn decode_metadata(data: &[u8]) -> Result<SSRIMetadata, ArgError> {
+    SSRIMetadata::decode(&decode_hex(data)?).map_err(|_| ArgError::Molecule)
+}
+
+/// Decodes the `to_lock_vec` and `to_amount_vec` arguments, which must be
+/// non-empty and name the same number of recipients.
+pub fn decode_recipients(
+    to_lock_vec: &[u8],
+    to_amount_vec: &[u8],
+) -> Result<(Vec<Script>, Vec<u128>), ArgError> {
+    // A missing argument is invalid, not malformed molecule.
+    if to_lock_vec.is_empty() || to_amount_vec.is_empty() {
+        return Err(ArgError::Invalid);
+    }
+    let locks = ScriptLikeVec::decode(&decode_hex(to_lock_vec)?)
+        .map_err(|_| ArgError::Molecule)?
+        .into_scripts();
+    let amounts = decode_amount_vec(&decode_hex(to_amount_vec)?).map_err(|_| ArgError::Molecule)?;
+    if locks.is_empty() || locks.len() != amounts.len() {
+        return Err(ArgError::Invalid);
+    }
+    Ok((locks, amounts))
+}
+
+/// Decodes the optional `immutable` flag of `SSRIUDT.create`: absent, empty
+/// or `00` is false, `01` is true.
+pub fn decode_flag(data: Option<&[u8]>) -> Result<bool, ArgError> {
+    match data.map(decode_hex).transpose()?.as_deref() {
+        None | Some([]) | Some([0]) => Ok(false),
+        Some([1]) => Ok(true),
+        Some(_) => Err(ArgError::Invalid),
+    }
+}
+
+/// Decodes a little-endian `u32` argument.
+pub fn decode_u32(data: &[u8]) -> Result<u32, ArgError> {
+    let bytes = decode_hex(data)?;
+    Ok(u32::from_le_bytes(
+        bytes.try_into().map_err(|_| ArgError::Invalid)?,
+    ))
+}
@@ -0,0 +1,80 @@
+//! Exit codes of the `ssri-udt` contract, one per variant of its `Error`
+//! enum, which takes its discriminants from here. Clients and tests match
+//! exit codes and `VerifyResult::error_code` against these names.
+
+// * CKB Error
+pub const INDEX_OUT_OF_BOUND: i8 = 1;
+pub const ITEM_MISSING: i8 = 2;
+pub const LENGTH_NOT_ENOUGH: i8 = 3;
+pub const ENCODING: i8 = 4;
+pub const SPAWN_EXCEEDED_MAX_CONTENT_LENGTH: i8 = 5;
+pub const SPAWN_WRONG_MEMORY_LIMIT: i8 = 6;
+pub const SPAWN_EXCEEDED_MAX_PEAK_MEMORY: i8 = 7;
+
+// * Rust Error
+pub const UTF8_ERROR: i8 = 8;
+
+// * SSRI Error
+pub const SSRI_METHODS_NOT_FOUND: i8 = 9;
+pub const SSRI_METHODS_ARGS_INVALID: i8 = 10;
+pub const SSRI_METHODS_NOT_IMPLEMENTED: i8 = 11;
+pub const SSRI_METHOD_REQUIRE_HIGHER_LEVEL: i8 = 12;
+pub const INVALID_VM_VERSION: i8 = 13;
+
+// * Molecule Error
+pub const MOLECULE_VERIFICATION_ERROR: i8 = 14;
+
+// * Serde Molecule Error
+pub const SERDE_MOLECULE_ERROR_WITH_MESSAGE: i8 = 15;
+pub const MISMATCHED_LENGTH: i8 = 16;
+pub const SERDE_MOLECULE_LENGTH_NOT_ENOUGH: i8 = 17;
+pub const UNIMPLEMENTED: i8 = 18;
+pub const ASSEMBLE_FIXVEC: i8 = 19;
+pub const INVALID_FIXVEC: i8 = 20;
+pub const MISMATCHED_TABLE_FIELD_COUNT: i8 = 21;
+pub const OVERFLOW: i8 = 22;
+pub const INVALID_ARRAY: i8 = 23;
+pub const INVALID_STRUCT_FIELD: i8 = 24;
+pub const INVALID_MAP: i8 = 25;
+pub const INVALID_TABLE: i8 = 26;
+pub const INVALID_TABLE_LENGTH: i8 = 27;
+pub const INVALID_TABLE_HEADER: i8 = 28;
+pub const INVALID_TABLE_COUNT: i8 = 29;
+pub const MIX_TABLE_AND_STRUCT: i8 = 30;
+pub const INVALID_CHAR: i8 = 31;
+
+// * UDT Error
+pub const INSUFFICIENT_BALANCE: i8 = 32;
+pub const NO_MINT_PERMISSION: i8 = 33;
+pub const NO_BURN_PERMISSION: i8 = 34;
+
+// * SSRI Config Error
+pub const INVALID_UDT_ARGS: i8 = 35;
+pub const SSRI_CONFIG_NOT_FOUND: i8 = 36;
+pub const SSRI_CONFIG_INVALID_DATA_FORMAT: i8 = 37;
+pub const INVALID_TRANSACTION_INPUTS: i8 = 38;
+
+// * SSRI Metadata Error
+pub const INVALID_METADATA_DECIMALS: i8 = 39;
+pub const EMPTY_METADATA_NAME: i8 = 40;
+pub const METADATA_NAME_TOO_LONG: i8 = 41;
+pub const EMPTY_METADATA_SYMBOL: i8 = 42;
+pub const METADATA_SYMBOL_TOO_LONG: i8 = 43;
+pub const INVALID_METADATA_SYMBOL_CHARACTER: i8 = 44;
+pub const INVALID_METADATA_ICON_SCHEME: i8 = 45;
+pub const METADATA_ICON_TOO_LARGE: i8 = 46;
+pub const METADATA_ALREADY_MIGRATED: i8 = 47;
+pub const METADATA_DESCRIPTION_TOO_LONG: i8 = 48;
+pub const INVALID_METADATA_LINK: i8 = 49;
+pub const TOO_MANY_METADATA_SOCIAL_LINKS: i8 = 50;
+pub const METADATA_CATEGORY_TOO_LONG: i8 = 51;
+pub const METADATA_EXTRA_TOO_LARGE: i8 = 52;
+pub const METADATA_EXTRA_KEY_NOT_FOUND: i8 = 53;
+pub const AMBIGUOUS_METADATA_ICON: i8 = 54;
+pub const METADATA_ICON_NOT_FOUND: i8 = 55;
+pub const METADATA_ICON_HASH_MISMATCH: i8 = 56;
+pub const INVALID_METADATA_LOCALE: i8 = 57;
+pub const TOO_MANY_METADATA_LOCALES: i8 = 58;
+pub const IMMUTABLE_METADATA: i8 = 59;
+pub const CONFLICTING_SSRI_CONFIG: i8 = 60;
+pub const AMOUNT_OVERFLOW: i8 = 61;
@@ -0,0 +1,30 @@
+#![no_std]
+//! Types and encoders shared by the `ssri-udt` contract and off-chain clients.
+//!
+//! Everything that crosses the SSRI boundary (metadata cell data, method
+//! arguments, Type ID args) is defined here once, so the contract and backend
+//! services always agree on the wire format.
+
+extern crate alloc;
+
+pub mod argv;
+pub mod codec;
+pub mod decode;
+pub mod error_code;
+pub mod metadata;
+pub mod report;
+pub mod singleton;
+pub mod type_id;
+pub mod udt_args;
+
+pub use codec::{decode_amount_vec, encode_amount_vec, ScriptLikeVec};
+pub use decode::ArgError;
+pub use metadata::{
+    IconDataRef, IconRef, LocalizedText, MetadataError, SSRIMetadata, SSRIMetadataV1,
+    SSRIMetadataV2,
+};
+pub use report::{
+    CreatedScripts, LockDelta, MetadataRecord, TransactionReport, VerificationPath, VerifyResult,
+};
+pub use singleton::MetadataSingleton;
+pub use udt_args::UDTArgs;
@@ -0,0 +1,426 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use ckb_hash::blake2b_256;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use serde_molecule::{dynvec_serde, from_slice, to_vec};
+
+/// Layout of metadata cells created before the `version` field existed.
+pub const METADATA_VERSION_1: u8 = 1;
+/// Layout that added `version`, before the explorer fields.
+pub const METADATA_VERSION_2: u8 = 2;
+/// Layout that added the explorer fields, before `icon_ref`.
+pub const METADATA_VERSION_3: u8 = 3;
+/// Layout that added `icon_ref`, before `localized`.
+pub const METADATA_VERSION_4: u8 = 4;
+/// Layout written by `SSRIUDT.create` and `SSRIUDT.migrate_metadata`.
+pub const CURRENT_METADATA_VERSION: u8 = 5;
+
+/// Largest `decimals` whose scaling factor still fits a `u128` amount.
+pub const MAX_DECIMALS: u8 = 38;
+pub const MAX_NAME_LEN: usize = 64;
+pub const MAX_SYMBOL_LEN: usize = 16;
+/// Cap on the icon, in bytes, which also bounds the metadata cell capacity.
+pub const MAX_ICON_LEN: usize = 8 * 1024;
+pub const MAX_DESCRIPTION_LEN: usize = 1024;
+/// Cap on `website` and on each social link.
+pub const MAX_LINK_LEN: usize = 256;
+pub const MAX_SOCIAL_LINKS: usize = 8;
+pub const MAX_CATEGORY_LEN: usize = 32;
+pub const MAX_EXTRA_ENTRIES: usize = 16;
+pub const MAX_EXTRA_KEY_LEN: usize = 64;
+pub const MAX_EXTRA_VALUE_LEN: usize = 1024;
+pub const MAX_LOCALES: usize = 16;
+/// Longest BCP 47 tag that well-formed locales in practice reach.
+pub const MAX_LOCALE_LEN: usize = 35;
+
+/// Token metadata stored in the Type ID cell referenced by the UDT args.
+///
+/// From version 2 on, `version` follows the v1 fields and newer versions only
+/// append fields after it. Decoders ignore trailing fields they do not know,
+/// so use [`SSRIMetadata::decode`], which also accepts v1 cells.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SSRIMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub icon: String,
+    /// Layout version the metadata was decoded from.
+    #[serde(default = "current_metadata_version")]
+    pub version: u8,
+    #[serde(default)]
+    pub description: String,
+    /// Project homepage, an https:// URL or empty.
+    #[serde(default)]
+    pub website: String,
+    /// https:// URLs of the project's social accounts.
+    #[serde(default, with = "dynvec_serde")]
+    pub social_links: Vec<String>,
+    /// Free-form token category, such as `stablecoin` or `governance`.
+    #[serde(default)]
+    pub category: String,
+    /// Arbitrary key/value pairs, read with `SSRIUDT.extra`.
+    #[serde(default)]
+    pub extra: BTreeMap<String, Vec<u8>>,
+    /// Icon kept in a cell of its own, returned by `UDT.icon` in place of the
+    /// inline `icon`, which must then be empty.
+    #[serde(default)]
+    pub icon_ref: Option<IconRef>,
+    /// Per-locale name and description, keyed by BCP 47 tag such as `zh-CN`.
+    #[serde(default)]
+    pub localized: BTreeMap<String, LocalizedText>,
+}
+
+/// Translations for one locale. An empty field falls back to the default.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LocalizedText {
+    pub name: String,
+    pub description: String,
+}
+
+/// Where `UDT.icon` finds an icon stored outside the metadata cell. The
+/// referenced cell data is the icon, returned as is.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum IconRef {
+    /// The live cell typed by the Type ID script with these args, so the
+    /// icon owner can replace it.
+    TypeId([u8; 32]),
+    /// A fixed cell whose data must hash to `data_hash`.
+    DataHash(IconDataRef),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IconDataRef {
+    pub tx_hash: [u8; 32],
+    pub index: u32,
+    /// CKB blake2b-256 of the icon cell data.
+    pub data_hash: [u8; 32],
+}
+
+impl IconDataRef {
+    /// Whether `data` is the icon this reference commits to.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        blake2b_256(data) == self.data_hash
+    }
+}
+
+fn current_metadata_version() -> u8 {
+    CURRENT_METADATA_VERSION
+}
+
+impl Default for SSRIMetadata {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            symbol: String::new(),
+            decimals: 0,
+            icon: String::new(),
+            version: CURRENT_METADATA_VERSION,
+            description: String::new(),
+            website: String::new(),
+            social_links: Vec::new(),
+            category: String::new(),
+            extra: BTreeMap::new(),
+            icon_ref: None,
+            localized: BTreeMap::new(),
+        }
+    }
+}
+
+/// The v1 layout: a four-field table without `version`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SSRIMetadataV1 {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub icon: String,
+}
+
+impl From<SSRIMetadataV1> for SSRIMetadata {
+    fn from(v1: SSRIMetadataV1) -> Self {
+        Self {
+            name: v1.name,
+            symbol: v1.symbol,
+            decimals: v1.decimals,
+            icon: v1.icon,
+            version: METADATA_VERSION_1,
+            ..Default::default()
+        }
+    }
+}
+
+/// The v2 layout: the v1 fields followed by `version`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SSRIMetadataV2 {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub icon: String,
+    pub version: u8,
+}
+
+impl From<SSRIMetadataV2> for SSRIMetadata {
+    fn from(v2: SSRIMetadataV2) -> Self {
+        Self {
+            name: v2.name,
+            symbol: v2.symbol,
+            decimals: v2.decimals,
+            icon: v2.icon,
+            version: v2.version,
+            ..Default::default()
+        }
+    }
+}
+
+/// Number of fields in a molecule table, read from its header.
+fn table_field_count(data: &[u8]) -> Option<usize> {
+    let total_size = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?) as usize;
+    if total_size == 4 {
+        return Some(0);
+    }
+    let first_offset = u32::from_le_bytes(data.get(4..8)?.try_into().ok()?) as usize;
+    (first_offset / 4).checked_sub(1)
+}
+
+/// Splits a molecule table into its raw fields.
+fn table_fields(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let count = table_field_count(data)?;
+    if u32::from_le_bytes(data.get(0..4)?.try_into().ok()?) as usize != data.len() {
+        return None;
+    }
+    let mut offsets = (0..count)
+        .map(|i| {
+            let at = 4 + 4 * i;
+            Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?) as usize)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    offsets.push(data.len());
+    offsets
+        .windows(2)
+        .map(|pair| data.get(pair[0]..pair[1]))
+        .collect()
+}
+
+fn assemble_table(fields: &[&[u8]]) -> Vec<u8> {
+    let header_len = 4 + 4 * fields.len();
+    let total_len = header_len + fields.iter().map(|field| field.len()).sum::<usize>();
+    let mut table = Vec::with_capacity(total_len);
+    table.extend_from_slice(&(total_len as u32).to_le_bytes());
+    let mut offset = header_len;
+    for field in fields {
+        table.extend_from_slice(&(offset as u32).to_le_bytes());
+        offset += field.len();
+    }
+    for field in fields {
+        table.extend_from_slice(field);
+    }
+    table
+}
+
+/// First rule an `SSRIMetadata` breaks. Lengths are in UTF-8 bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataError {
+    InvalidDecimals,
+    EmptyName,
+    NameTooLong,
+    EmptySymbol,
+    SymbolTooLong,
+    InvalidSymbolCharacter,
+    InvalidIconScheme,
+    IconTooLarge,
+    DescriptionTooLong,
+    InvalidLink,
+    TooManySocialLinks,
+    CategoryTooLong,
+    ExtraTooLarge,
+    AmbiguousIcon,
+    InvalidLocale,
+    TooManyLocales,
+}
+
+impl fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDecimals => write!(f, "decimals must be at most {}", MAX_DECIMALS),
+            Self::EmptyName => write!(f, "name must not be empty"),
+            Self::NameTooLong => write!(f, "name must be at most {} bytes", MAX_NAME_LEN),
+            Self::EmptySymbol => write!(f, "symbol must not be empty"),
+            Self::SymbolTooLong => write!(f, "symbol must be at most {} bytes", MAX_SYMBOL_LEN),
+            Self::InvalidSymbolCharacter => {
+                write!(f, "symbol may only contain A-Z, a-z, 0-9, '-', '_' and '.'")
+            }
+            Self::InvalidIconScheme => write!(f, "icon must be an https:// URL or a data: URI"),
+            Self::IconTooLarge => write!(f, "icon must be at most {} bytes", MAX_ICON_LEN),
+            Self::DescriptionTooLong => {
+                write!(f, "description must be at most {} bytes", MAX_DESCRIPTION_LEN)
+            }
+            Self::InvalidLink => write!(
+                f,
+                "website and social links must be https:// URLs of at most {} bytes",
+                MAX_LINK_LEN
+            ),
+            Self::TooManySocialLinks => {
+                write!(f, "at most {} social links are allowed", MAX_SOCIAL_LINKS)
+            }
+            Self::CategoryTooLong => {
+                write!(f, "category must be at most {} bytes", MAX_CATEGORY_LEN)
+            }
+            Self::ExtraTooLarge => write!(
+                f,
+                "extra may hold at most {} entries, with keys of at most {} bytes and values of at most {} bytes",
+                MAX_EXTRA_ENTRIES, MAX_EXTRA_KEY_LEN, MAX_EXTRA_VALUE_LEN
+            ),
+            Self::AmbiguousIcon => write!(f, "icon must be empty when icon_ref is set"),
+            Self::InvalidLocale => write!(
+                f,
+                "locales must be 1 to {} bytes of A-Z, a-z, 0-9 and '-'",
+                MAX_LOCALE_LEN
+            ),
+            Self::TooManyLocales => write!(f, "at most {} locales are allowed", MAX_LOCALES),
+        }
+    }
+}
+
+impl SSRIMetadata {
+    /// Decodes any metadata layout: older cells come back with their
+    /// `version` and defaults for the fields they lack, and fields appended by
+    /// versions newer than this crate are skipped.
+    pub fn decode(data: &[u8]) -> Result<Self, serde_molecule::Error> {
+        let fields = table_fields(data).ok_or(serde_molecule::Error::InvalidTableHeader)?;
+        match fields.len() {
+            0..=3 => return Err(serde_molecule::Error::MismatchedTableFieldCount),
+            4 => return from_slice::<SSRIMetadataV1>(data, false).map(Into::into),
+            _ => {}
+        }
+        let defaults = to_vec(&Self::default(), false)?;
+        let default_fields =
+            table_fields(&defaults).ok_or(serde_molecule::Error::InvalidTableHeader)?;
+        if fields.len() >= default_fields.len() {
+            return from_slice(data, false);
+        }
+        // Fields are append-only, so an older layout is a prefix of this one.
+        let padded = assemble_table(&[&fields[..], &default_fields[fields.len()..]].concat());
+        from_slice(&padded, false)
+    }
+
+    /// Encodes in the current layout, whatever version `self` was decoded
+    /// from.
+    pub fn encode(&self) -> Result<Vec<u8>, serde_molecule::Error> {
+        to_vec(
+            &Self {
+                version: CURRENT_METADATA_VERSION,
+                ..self.clone()
+            },
+            false,
+        )
+    }
+
+    /// Checks the rules enforced by `SSRIUDT.create`, and by `verify_mint` on
+    /// metadata in the current layout. An empty icon means the token has none.
+    pub fn validate(&self) -> Result<(), MetadataError> {
+        if self.decimals > MAX_DECIMALS {
+            return Err(MetadataError::InvalidDecimals);
+        }
+        if self.name.is_empty() {
+            return Err(MetadataError::EmptyName);
+        }
+        if self.name.len() > MAX_NAME_LEN {
+            return Err(MetadataError::NameTooLong);
+        }
+        if self.symbol.is_empty() {
+            return Err(MetadataError::EmptySymbol);
+        }
+        if self.symbol.len() > MAX_SYMBOL_LEN {
+            return Err(MetadataError::SymbolTooLong);
+        }
+        if !self
+            .symbol
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_' | b'.'))
+        {
+            return Err(MetadataError::InvalidSymbolCharacter);
+        }
+        if !self.icon.is_empty()
+            && !self.icon.starts_with("https://")
+            && !self.icon.starts_with("data:")
+        {
+            return Err(MetadataError::InvalidIconScheme);
+        }
+        if self.icon.len() > MAX_ICON_LEN {
+            return Err(MetadataError::IconTooLarge);
+        }
+        if self.icon_ref.is_some() && !self.icon.is_empty() {
+            return Err(MetadataError::AmbiguousIcon);
+        }
+        if self.description.len() > MAX_DESCRIPTION_LEN {
+            return Err(MetadataError::DescriptionTooLong);
+        }
+        if !self.website.is_empty() && !is_valid_link(&self.website) {
+            return Err(MetadataError::InvalidLink);
+        }
+        if self.social_links.len() > MAX_SOCIAL_LINKS {
+            return Err(MetadataError::TooManySocialLinks);
+        }
+        if !self.social_links.iter().all(|link| is_valid_link(link)) {
+            return Err(MetadataError::InvalidLink);
+        }
+        if self.category.len() > MAX_CATEGORY_LEN {
+            return Err(MetadataError::CategoryTooLong);
+        }
+        if self.extra.len() > MAX_EXTRA_ENTRIES
+            || self.extra.iter().any(|(key, value)| {
+                key.len() > MAX_EXTRA_KEY_LEN || value.len() > MAX_EXTRA_VALUE_LEN
+            })
+        {
+            return Err(MetadataError::ExtraTooLarge);
+        }
+        if self.localized.len() > MAX_LOCALES {
+            return Err(MetadataError::TooManyLocales);
+        }
+        for (locale, text) in &self.localized {
+            if locale.is_empty()
+                || locale.len() > MAX_LOCALE_LEN
+                || !locale
+                    .bytes()
+                    .all(|c| c.is_ascii_alphanumeric() || c == b'-')
+            {
+                return Err(MetadataError::InvalidLocale);
+            }
+            if text.name.len() > MAX_NAME_LEN {
+                return Err(MetadataError::NameTooLong);
+            }
+            if text.description.len() > MAX_DESCRIPTION_LEN {
+                return Err(MetadataError::DescriptionTooLong);
+            }
+        }
+        Ok(())
+    }
+
+    /// Translations for `locale`: an exact match, else its primary language
+    /// subtag (`zh` for `zh-CN`).
+    fn localized_text(&self, locale: &str) -> Option<&LocalizedText> {
+        self.localized.get(locale).or_else(|| {
+            let (language, _) = locale.split_once('-')?;
+            self.localized.get(language)
+        })
+    }
+
+    /// `name` in `locale`, falling back to the default `name`.
+    pub fn name_in(&self, locale: &str) -> &str {
+        match self.localized_text(locale) {
+            Some(text) if !text.name.is_empty() => &text.name,
+            _ => &self.name,
+        }
+    }
+
+    /// `description` in `locale`, falling back to the default `description`.
+    pub fn description_in(&self, locale: &str) -> &str {
+        match self.localized_text(locale) {
+            Some(text) if !text.description.is_empty() => &text.description,
+            _ => &self.description,
+        }
+    }
+}
+
+fn is_valid_link(link: &str) -> bool {
+    link.starts_with("https://") && link.len() <= MAX_LINK_LEN
+}
@@ -0,0 +1,186 @@
+use alloc::{string::String, vec::Vec};
+use ckb_ssri_std::public_module_traits::udt::ScriptLike;
+use ckb_std::ckb_types::{
+    packed::{OutPoint, Script},
+    prelude::{Builder, Entity, Pack, Unpack},
+};
+use serde::{Deserialize, Serialize};
+use serde_molecule::dynvec_serde;
+
+use crate::{
+    codec::{from_script_like, to_script_like},
+    SSRIMetadata,
+};
+
+/// Branch of the contract `fallback` a transaction goes through, decided by
+/// comparing the UDT input and output sums.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationPath {
+    /// Outputs exceed inputs: `verify_mint`, which requires owner mode.
+    Mint = 0,
+    /// Outputs equal inputs: `verify_transfer`.
+    Transfer = 1,
+    /// Inputs exceed outputs: rejected with `InsufficientBalance`, as burning
+    /// is not supported.
+    Rejected = 2,
+}
+
+impl TryFrom<u8> for VerificationPath {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Mint),
+            1 => Ok(Self::Transfer),
+            2 => Ok(Self::Rejected),
+            _ => Err(value),
+        }
+    }
+}
+
+/// UDT held by one lock hash in the inputs and outputs of a transaction.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LockDelta {
+    pub lock_hash: [u8; 32],
+    pub input_amount: u128,
+    pub output_amount: u128,
+}
+
+/// Result of `SSRIUDT.describe_transaction`: how the contract sees the UDT
+/// cells of a transaction, and what `fallback` would do with it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TransactionReport {
+    /// One entry per lock hash holding the UDT, sorted by lock hash.
+    #[serde(with = "dynvec_serde")]
+    pub lock_deltas: Vec<LockDelta>,
+    pub input_amount: u128,
+    pub output_amount: u128,
+    /// Outputs in excess of inputs.
+    pub minted: u128,
+    /// Inputs in excess of outputs.
+    pub burned: u128,
+    /// Input amount carried over to the outputs, `min(inputs, outputs)`.
+    pub transferred: u128,
+    /// Whether an input carries the owner lock: the lock of the metadata cell,
+    /// or the one immutable args commit to. It does not say whether the
+    /// metadata is valid; `SSRIUDT.verify` checks that.
+    pub owner_mode: bool,
+    /// A [`VerificationPath`] discriminant.
+    pub path: u8,
+}
+
+impl TransactionReport {
+    pub fn verification_path(&self) -> Option<VerificationPath> {
+        VerificationPath::try_from(self.path).ok()
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, serde_molecule::Error> {
+        serde_molecule::to_vec(self, false)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, serde_molecule::Error> {
+        serde_molecule::from_slice(data, false)
+    }
+}
+
+/// Result of `SSRIUDT.verify`: whether the transaction passes the type script
+/// and, if not, the contract error it fails with.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VerifyResult {
+    pub passed: bool,
+    /// Exit code of the contract `Error` variant, or 0 when passed.
+    pub error_code: u8,
+    /// Name of the contract `Error` variant, or empty when passed.
+    pub error: String,
+}
+
+impl VerifyResult {
+    pub fn encode(&self) -> Result<Vec<u8>, serde_molecule::Error> {
+        serde_molecule::to_vec(self, false)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, serde_molecule::Error> {
+        serde_molecule::from_slice(data, false)
+    }
+}
+
+/// Result of `SSRIUDT.created_scripts`: the scripts a creation transaction
+/// brings into existence.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CreatedScripts {
+    /// Type ID script of the metadata cell.
+    pub metadata_type_script: ScriptLike,
+    /// ssri-udt type script whose args are the metadata Type ID args.
+    pub udt_type_script: ScriptLike,
+}
+
+impl CreatedScripts {
+    pub fn new(metadata_type_script: &Script, udt_type_script: &Script) -> Self {
+        Self {
+            metadata_type_script: to_script_like(metadata_type_script),
+            udt_type_script: to_script_like(udt_type_script),
+        }
+    }
+
+    /// Returns the metadata Type ID script and the UDT type script.
+    pub fn into_scripts(self) -> (Script, Script) {
+        (
+            from_script_like(self.metadata_type_script),
+            from_script_like(self.udt_type_script),
+        )
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, serde_molecule::Error> {
+        serde_molecule::to_vec(self, false)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, serde_molecule::Error> {
+        serde_molecule::from_slice(data, false)
+    }
+}
+
+/// Result of `SSRIUDT.metadata`: the metadata cell named by the UDT args,
+/// read in one call.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MetadataRecord {
+    /// The cell data, decoded into the current layout. `version` stays the
+    /// layout of the cell, so a version below `CURRENT_METADATA_VERSION`
+    /// tells clients the cell still needs `migrate_metadata`.
+    pub metadata: SSRIMetadata,
+    /// Out point of the metadata cell.
+    pub tx_hash: [u8; 32],
+    pub index: u32,
+    /// Lock of the metadata cell, whose inputs may mint.
+    pub owner_lock: ScriptLike,
+}
+
+impl MetadataRecord {
+    pub fn new(metadata: SSRIMetadata, out_point: &OutPoint, owner_lock: &Script) -> Self {
+        Self {
+            metadata,
+            tx_hash: out_point.tx_hash().unpack(),
+            index: out_point.index().unpack(),
+            owner_lock: to_script_like(owner_lock),
+        }
+    }
+
+    pub fn out_point(&self) -> OutPoint {
+        OutPoint::new_builder()
+            .tx_hash(self.tx_hash.pack())
+            .index(self.index.pack())
+            .build()
+    }
+
+    pub fn owner_lock(&self) -> Script {
+        from_script_like(self.owner_lock.clone())
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, serde_molecule::Error> {
+        serde_molecule::to_vec(self, false)
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, serde_molecule::Error> {
+        serde_molecule::from_slice(data, false)
+    }
+}
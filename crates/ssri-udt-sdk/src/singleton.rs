@@ -0,0 +1,81 @@
+use ckb_std::ckb_types::{
+    core::ScriptHashType,
+    packed::Script,
+    prelude::{Builder, Entity, Pack},
+};
+
+use crate::type_id::TYPE_ID_SCRIPT_CODE_HASH;
+
+/// Data hash (`data1`) of the unique cell script deployed on mainnet.
+pub const UNIQUE_CELL_MAINNET_CODE_HASH: [u8; 32] = [
+    0x2c, 0x8c, 0x11, 0xc9, 0x85, 0xda, 0x60, 0xb0, 0xa3, 0x30, 0xc6, 0x1a, 0x85, 0x50, 0x74, 0x16,
+    0xd6, 0x38, 0x2c, 0x13, 0x0b, 0xa6, 0x7f, 0x0c, 0x47, 0xab, 0x07, 0x1e, 0x00, 0xae, 0xc6, 0x28,
+];
+
+/// Type hash of the unique cell script deployed on testnet, which is a
+/// different build than mainnet's.
+pub const UNIQUE_CELL_TESTNET_CODE_HASH: [u8; 32] = [
+    0x8e, 0x34, 0x1b, 0xcf, 0xec, 0x63, 0x93, 0xdc, 0xd4, 0x1e, 0x63, 0x57, 0x33, 0xff, 0x2d, 0xca,
+    0x00, 0xa6, 0xaf, 0x54, 0x69, 0x49, 0xf7, 0x0c, 0x57, 0xa7, 0x06, 0xc0, 0xf3, 0x44, 0xdf, 0x8b,
+];
+
+/// Code hash of the unique cell script on the network this crate is built
+/// for: testnet with the `testnet` feature, mainnet otherwise.
+#[cfg(not(feature = "testnet"))]
+pub const UNIQUE_CELL_CODE_HASH: [u8; 32] = UNIQUE_CELL_MAINNET_CODE_HASH;
+#[cfg(feature = "testnet")]
+pub const UNIQUE_CELL_CODE_HASH: [u8; 32] = UNIQUE_CELL_TESTNET_CODE_HASH;
+
+/// Hash type `UNIQUE_CELL_CODE_HASH` is referenced by.
+#[cfg(not(feature = "testnet"))]
+pub const UNIQUE_CELL_HASH_TYPE: ScriptHashType = ScriptHashType::Data1;
+#[cfg(feature = "testnet")]
+pub const UNIQUE_CELL_HASH_TYPE: ScriptHashType = ScriptHashType::Type;
+
+/// Type script keeping the metadata cell the only live cell of its kind, so
+/// UDT args can name the metadata by the args of that script. Selector 1 is
+/// reserved for a metadata-guard script, to be added once one is deployed
+/// with a fixed code hash, and is rejected until then.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataSingleton {
+    /// The built-in Type ID script, with 32-byte args.
+    TypeId = 0,
+    /// The community unique cell script, with 20-byte args.
+    UniqueCell = 2,
+}
+
+impl TryFrom<u8> for MetadataSingleton {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::TypeId),
+            2 => Ok(Self::UniqueCell),
+            _ => Err(value),
+        }
+    }
+}
+
+impl MetadataSingleton {
+    /// Length of the args the script gives its cells.
+    pub fn args_len(self) -> usize {
+        match self {
+            Self::TypeId => 32,
+            Self::UniqueCell => 20,
+        }
+    }
+
+    /// The type script of the metadata cell with these args.
+    pub fn script(self, args: &[u8]) -> Script {
+        let (code_hash, hash_type) = match self {
+            Self::TypeId => (TYPE_ID_SCRIPT_CODE_HASH, ScriptHashType::Type),
+            Self::UniqueCell => (UNIQUE_CELL_CODE_HASH, UNIQUE_CELL_HASH_TYPE),
+        };
+        Script::new_builder()
+            .code_hash(code_hash.pack())
+            .hash_type(hash_type.into())
+            .args(args.to_vec().pack())
+            .build()
+    }
+}
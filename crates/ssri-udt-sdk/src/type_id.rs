@@ -0,0 +1,47 @@
+use ckb_hash::new_blake2b;
+use ckb_std::ckb_types::{
+    core::ScriptHashType,
+    packed::{CellInput, Script, Transaction},
+    prelude::*,
+};
+
+pub const TYPE_ID_SCRIPT_CODE_HASH: [u8; 32] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x54, 0x59, 0x50, 0x45, 0x5f, 0x49, 0x44,
+];
+
+/// Computes Type ID args the same way the built-in Type ID script validates
+/// them: blake2b over the first input and the `u64` index of the new output.
+pub fn calc_type_id(first_input: &CellInput, output_index: u64) -> [u8; 32] {
+    let mut hasher = new_blake2b();
+    hasher.update(first_input.as_slice());
+    hasher.update(&output_index.to_le_bytes());
+    let mut ret = [0; 32];
+    hasher.finalize(&mut ret);
+    ret
+}
+
+pub fn type_id_script(type_id: &[u8; 32]) -> Script {
+    Script::new_builder()
+        .code_hash(TYPE_ID_SCRIPT_CODE_HASH.pack())
+        .hash_type(ScriptHashType::Type.into())
+        .args(type_id.to_vec().pack())
+        .build()
+}
+
+/// Finds the Type ID script of the metadata cell that `SSRIUDT.create` added
+/// to `tx`: the output whose Type ID args match its own index.
+pub fn find_created_type_id_script(tx: &Transaction) -> Option<Script> {
+    let first_input = tx.raw().inputs().get(0)?;
+    tx.raw()
+        .outputs()
+        .into_iter()
+        .enumerate()
+        .find_map(|(index, output)| {
+            let expected = type_id_script(&calc_type_id(&first_input, index as u64));
+            output
+                .type_()
+                .to_opt()
+                .filter(|type_script| type_script.as_slice() == expected.as_slice())
+        })
+}
@@ -0,0 +1,94 @@
+use alloc::vec::Vec;
+use ckb_hash::blake2b_256;
+use ckb_std::ckb_types::packed::Script;
+
+use crate::{singleton::MetadataSingleton, type_id::type_id_script};
+
+/// How the args of an ssri-udt type script locate the token metadata, told
+/// apart by length.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UDTArgs {
+    /// 32 bytes: the Type ID args of a metadata cell that its owner can
+    /// update. Owner mode is an input with that cell's lock.
+    TypeId([u8; 32]),
+    /// A [`MetadataSingleton`] discriminant followed by the args of that
+    /// script, 33 or 21 bytes. Works like Type ID args with the metadata cell
+    /// guarded by the named script.
+    Singleton {
+        singleton: MetadataSingleton,
+        args: Vec<u8>,
+    },
+    /// 64 bytes: the owner lock hash, sUDT-style, followed by the blake2b hash
+    /// of the metadata cell data. The metadata can never change, and any cell
+    /// carrying exactly that data serves it.
+    Immutable {
+        owner_lock_hash: [u8; 32],
+        metadata_hash: [u8; 32],
+    },
+}
+
+impl UDTArgs {
+    pub fn parse(args: &[u8]) -> Option<Self> {
+        match args.len() {
+            32 => Some(Self::TypeId(args.try_into().ok()?)),
+            64 => Some(Self::Immutable {
+                owner_lock_hash: args[..32].try_into().ok()?,
+                metadata_hash: args[32..].try_into().ok()?,
+            }),
+            _ => {
+                let (&kind, args) = args.split_first()?;
+                let singleton = MetadataSingleton::try_from(kind).ok()?;
+                (args.len() == singleton.args_len()).then(|| Self::Singleton {
+                    singleton,
+                    args: args.to_vec(),
+                })
+            }
+        }
+    }
+
+    /// Args naming the metadata cell guarded by `singleton` with `args`.
+    pub fn singleton(singleton: MetadataSingleton, args: &[u8]) -> Self {
+        Self::Singleton {
+            singleton,
+            args: args.to_vec(),
+        }
+    }
+
+    /// Immutable args committing to `metadata_data`, the encoded metadata.
+    pub fn immutable(owner_lock_hash: [u8; 32], metadata_data: &[u8]) -> Self {
+        Self::Immutable {
+            owner_lock_hash,
+            metadata_hash: blake2b_256(metadata_data),
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        match self {
+            Self::TypeId(type_id_args) => type_id_args.to_vec(),
+            Self::Singleton { singleton, args } => [&[*singleton as u8][..], args].concat(),
+            Self::Immutable {
+                owner_lock_hash,
+                metadata_hash,
+            } => [&owner_lock_hash[..], &metadata_hash[..]].concat(),
+        }
+    }
+
+    /// Type script of the metadata cell these args name, or `None` for
+    /// immutable args, which name no single cell.
+    pub fn metadata_type_script(&self) -> Option<Script> {
+        match self {
+            Self::TypeId(type_id_args) => Some(type_id_script(type_id_args)),
+            Self::Singleton { singleton, args } => Some(singleton.script(args)),
+            Self::Immutable { .. } => None,
+        }
+    }
+
+    /// Whether `data` is the metadata committed to by immutable args. Always
+    /// false for the other forms.
+    pub fn commits_to(&self, data: &[u8]) -> bool {
+        match self {
+            Self::TypeId(_) | Self::Singleton { .. } => false,
+            Self::Immutable { metadata_hash, .. } => blake2b_256(data) == *metadata_hash,
+        }
+    }
+}
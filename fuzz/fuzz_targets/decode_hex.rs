@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ssri_udt_fuzz::hex;
+use ssri_udt_sdk::{decode::decode_hex, ArgError};
+
+fuzz_target!(|data: &[u8]| {
+    match decode_hex(data) {
+        Ok(bytes) => assert_eq!(hex(&bytes), data.to_ascii_lowercase()),
+        Err(err) => assert_eq!(err, ArgError::Encoding),
+    }
+});
@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ssri_udt_fuzz::hex;
+use ssri_udt_sdk::{
+    decode::decode_metadata, metadata::CURRENT_METADATA_VERSION, ArgError, SSRIMetadata,
+};
+
+fuzz_target!(|data: &[u8]| {
+    match decode_metadata(&hex(data)) {
+        // Whatever layout was decoded must survive a round trip through the
+        // current one.
+        Ok(metadata) => {
+            let encoded = metadata.encode().expect("decoded metadata encodes");
+            assert_eq!(
+                SSRIMetadata::decode(&encoded).expect("encoded metadata decodes"),
+                SSRIMetadata {
+                    version: CURRENT_METADATA_VERSION,
+                    ..metadata
+                }
+            );
+        }
+        Err(err) => assert_eq!(err, ArgError::Molecule),
+    }
+});
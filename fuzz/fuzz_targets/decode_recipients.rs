@@ -0,0 +1,28 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ssri_udt_fuzz::{hex, split_two};
+use ssri_udt_sdk::{decode::decode_recipients, ArgError};
+
+fn header(bytes: &[u8], at: usize) -> usize {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as usize
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (locks, amounts) = split_two(data);
+    match decode_recipients(&hex(locks), &hex(amounts)) {
+        Ok((to_lock_vec, to_amount_vec)) => {
+            // The decoded counts must be the ones the molecule headers claim,
+            // and the length check must have held.
+            let lock_count = match header(locks, 0) {
+                4 => 0,
+                _ => header(locks, 4) / 4 - 1,
+            };
+            assert_eq!(to_lock_vec.len(), lock_count);
+            assert_eq!(to_amount_vec.len(), header(amounts, 0));
+            assert_eq!(to_lock_vec.len(), to_amount_vec.len());
+            assert!(!locks.is_empty() && !amounts.is_empty());
+        }
+        Err(err) => assert!(matches!(err, ArgError::Invalid | ArgError::Molecule)),
+    }
+});
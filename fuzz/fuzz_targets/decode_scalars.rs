@@ -0,0 +1,23 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ssri_udt_sdk::{
+    decode::{arg, decode_flag, decode_u32},
+    ArgError,
+};
+
+fuzz_target!(|data: &[u8]| {
+    match decode_flag(Some(data)) {
+        Ok(flag) => assert!(matches!((flag, data), (false, b"" | b"00") | (true, b"01"))),
+        Err(err) => assert!(matches!(err, ArgError::Invalid | ArgError::Encoding)),
+    }
+    match decode_u32(data) {
+        Ok(_) => assert_eq!(data.len(), 8),
+        Err(err) => assert!(matches!(err, ArgError::Invalid | ArgError::Encoding)),
+    }
+    // `arg` never indexes past the end of argv.
+    let argv = data.split(|&byte| byte == b' ').collect::<Vec<_>>();
+    for index in 0..=argv.len() {
+        assert_eq!(arg(&argv, index).ok(), argv.get(index).copied());
+    }
+});
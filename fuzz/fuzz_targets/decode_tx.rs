@@ -0,0 +1,26 @@
+#![no_main]
+
+use ckb_std::ckb_types::prelude::*;
+use libfuzzer_sys::fuzz_target;
+use ssri_udt_fuzz::hex;
+use ssri_udt_sdk::{
+    decode::{decode_optional_tx, decode_script, decode_tx},
+    ArgError,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let arg = hex(data);
+    match decode_tx(&arg) {
+        Ok(tx) => assert_eq!(tx.as_slice(), data),
+        Err(err) => assert_eq!(err, ArgError::Molecule),
+    }
+    match decode_optional_tx(&arg) {
+        Ok(Some(tx)) => assert_eq!(tx.as_slice(), data),
+        Ok(None) => assert!(data.is_empty()),
+        Err(err) => assert_eq!(err, ArgError::Molecule),
+    }
+    match decode_script(&arg) {
+        Ok(script) => assert_eq!(script.as_slice(), data),
+        Err(err) => assert_eq!(err, ArgError::Molecule),
+    }
+});
@@ -0,0 +1,19 @@
+//! Helpers shared by the fuzz targets.
+
+/// Hex-encodes `bytes` the way SSRI arguments arrive in argv, so targets can
+/// reach the molecule decoders instead of failing on the hex layer.
+pub fn hex(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| format!("{:02x}", byte).into_bytes())
+        .collect()
+}
+
+/// Splits the fuzz input into two arguments at the offset named by its first
+/// byte.
+pub fn split_two(data: &[u8]) -> (&[u8], &[u8]) {
+    let Some((&at, rest)) = data.split_first() else {
+        return (&[], &[]);
+    };
+    rest.split_at(usize::from(at).min(rest.len()))
+}
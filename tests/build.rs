@@ -0,0 +1,13 @@
+use molecule_codegen::{Compiler, Language};
+
+// Generates Rust code from the published molecule schema, so the tests can
+// compare serde_molecule encodings against it.
+fn main() {
+    println!("cargo:rerun-if-changed=../schemas/ssri_udt.mol");
+    Compiler::new()
+        .input_schema_file("../schemas/ssri_udt.mol")
+        .generate_code(Language::Rust)
+        .output_dir_set_default()
+        .run()
+        .expect("compile molecule schema");
+}
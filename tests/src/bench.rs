@@ -0,0 +1,148 @@
+// Cycle benchmarks for the UDT type script. Every scenario is verified and its
+// cycles compared with `tests/cycles_baseline.json`; a scenario costing more
+// than the baseline plus `CYCLE_REGRESSION_PERCENT` (default 5) percent fails
+// the test. Run with `UPDATE_CYCLE_BASELINE=1` to write the measured cycles as
+// the new baseline, and check the file in with the change that moved them.
+//
+// Burning is not supported by the contract, so there is no burn scenario yet.
+
+use std::{collections::BTreeMap, env, fs, path::PathBuf};
+
+use ckb_std::ckb_types::{bytes::Bytes, packed::*, prelude::*};
+use ckb_testtool::ckb_types::core::{TransactionBuilder, TransactionView};
+
+use crate::utils::{build_test_context, PausableUDTTestContext};
+
+const SIZES: [usize; 4] = [1, 10, 100, 500];
+const DEFAULT_REGRESSION_PERCENT: u64 = 5;
+const AMOUNT: u128 = 1000;
+
+/// Builds a scenario transaction with the given number of UDT cells.
+type BuildTx = fn(&mut PausableUDTTestContext, usize) -> TransactionView;
+
+fn baseline_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("cycles_baseline.json")
+}
+
+fn udt_output(test_context: &PausableUDTTestContext, lock: &Script) -> CellOutput {
+    CellOutput::new_builder()
+        .capacity(100u64.pack())
+        .lock(lock.clone())
+        .type_(Some(test_context.ssri_udt_type_script.clone()).pack())
+        .build()
+}
+
+/// `size` UDT cells of user A sent one-to-one to user B.
+fn transfer_tx(test_context: &mut PausableUDTTestContext, size: usize) -> TransactionView {
+    let mut tx = TransactionBuilder::default().cell_deps(vec![
+        test_context.ssri_udt_dep.clone(),
+        test_context.always_success_dep.clone(),
+    ]);
+    for _ in 0..size {
+        let input = udt_output(test_context, &test_context.normal_user_a_lock_script);
+        let out_point = test_context
+            .context
+            .create_cell(input, AMOUNT.pack().as_bytes());
+        tx = tx
+            .input(CellInput::new_builder().previous_output(out_point).build())
+            .output(udt_output(
+                test_context,
+                &test_context.normal_user_b_lock_script,
+            ))
+            .output_data(AMOUNT.pack().as_bytes().pack());
+    }
+    tx.build()
+}
+
+/// An admin input minting `size` UDT cells to user B.
+fn mint_tx(test_context: &mut PausableUDTTestContext, size: usize) -> TransactionView {
+    let admin_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let mut tx = TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(admin_out_point)
+                .build(),
+        )
+        .cell_deps(vec![
+            test_context.ssri_udt_dep.clone(),
+            test_context.always_success_dep.clone(),
+            test_context.ssri_metadata_dep.clone(),
+        ]);
+    for _ in 0..size {
+        tx = tx
+            .output(udt_output(
+                test_context,
+                &test_context.normal_user_b_lock_script,
+            ))
+            .output_data(AMOUNT.pack().as_bytes().pack());
+    }
+    tx.build()
+}
+
+fn measure() -> BTreeMap<String, u64> {
+    let scenarios: [(&str, BuildTx); 2] = [("transfer", transfer_tx), ("mint", mint_tx)];
+    let mut cycles = BTreeMap::new();
+    for (name, build_tx) in scenarios {
+        for size in SIZES {
+            let mut test_context = build_test_context();
+            let tx = build_tx(&mut test_context, size);
+            let scenario = format!("{}_{}", name, size);
+            let used = test_context
+                .context
+                .verify_tx(&tx, u64::MAX)
+                .unwrap_or_else(|err| panic!("{} failed: {}", scenario, err));
+            cycles.insert(scenario, used);
+        }
+    }
+    cycles
+}
+
+#[test]
+fn test_cycle_baseline() {
+    let measured = measure();
+    if env::var("UPDATE_CYCLE_BASELINE").is_ok() {
+        let json = serde_json::to_string_pretty(&measured).unwrap();
+        fs::write(baseline_path(), json + "\n").expect("write cycle baseline");
+        return;
+    }
+
+    let baseline: BTreeMap<String, u64> =
+        serde_json::from_str(&fs::read_to_string(baseline_path()).expect("read cycle baseline"))
+            .expect("parse cycle baseline");
+    let percent = env::var("CYCLE_REGRESSION_PERCENT")
+        .map(|percent| percent.parse().expect("CYCLE_REGRESSION_PERCENT"))
+        .unwrap_or(DEFAULT_REGRESSION_PERCENT);
+
+    let mut regressions = Vec::new();
+    for (scenario, &used) in &measured {
+        let Some(&expected) = baseline.get(scenario) else {
+            regressions.push(format!(
+                "{}: {} cycles, not in the baseline",
+                scenario, used
+            ));
+            continue;
+        };
+        println!(
+            "{:<14} {:>12} cycles (baseline {})",
+            scenario, used, expected
+        );
+        if used > expected + expected * percent / 100 {
+            regressions.push(format!(
+                "{}: {} cycles, {} in the baseline",
+                scenario, used, expected
+            ));
+        }
+    }
+    assert!(
+        regressions.is_empty(),
+        "cycles regressed by more than {}%:\n{}",
+        percent,
+        regressions.join("\n")
+    );
+}
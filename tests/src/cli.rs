@@ -0,0 +1,229 @@
+// NOTE: These tests build transactions with ssri-udt-cli against the ckb-testtool context and verify them on-chain.
+
+use ckb_std::ckb_types::{bytes::Bytes, packed::*, prelude::*};
+use ckb_testtool::ckb_types::core::ScriptHashType;
+use serde_json::json;
+use ssri_udt_cli::{to_ckb_cli_json, CliError, TxOptions, UdtTxBuilder};
+use ssri_udt_sdk::{
+    type_id::{calc_type_id, type_id_script},
+    SSRIMetadata, UDTArgs,
+};
+
+use crate::utils::{build_test_context, PausableUDTTestContext};
+
+const CKB: u64 = 100_000_000;
+
+fn options(test_context: &PausableUDTTestContext, change_lock: &Script) -> TxOptions {
+    TxOptions {
+        change_lock: change_lock.clone(),
+        fee: 100_000,
+        cell_deps: vec![test_context.always_success_dep.clone()],
+    }
+}
+
+fn capacity_cell(test_context: &mut PausableUDTTestContext, lock: &Script) -> OutPoint {
+    test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity((1000 * CKB).pack())
+            .lock(lock.clone())
+            .build(),
+        Bytes::new(),
+    )
+}
+
+fn udt_cell(test_context: &mut PausableUDTTestContext, lock: &Script, amount: u128) -> OutPoint {
+    test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity((1000 * CKB).pack())
+            .lock(lock.clone())
+            .type_(Some(test_context.ssri_udt_type_script.clone()).pack())
+            .build(),
+        Bytes::from(amount.to_le_bytes().to_vec()),
+    )
+}
+
+#[test]
+fn test_cli_create() {
+    let mut test_context = build_test_context();
+    let admin_lock = test_context.admin_lock_script.clone();
+    let input = capacity_cell(&mut test_context, &admin_lock);
+    let metadata = SSRIMetadata {
+        name: String::from("CLI UDT"),
+        symbol: String::from("CLI"),
+        decimals: 8,
+        icon: String::new(),
+        ..Default::default()
+    };
+
+    let builder = UdtTxBuilder::new(
+        &test_context.context,
+        test_context.ssri_udt_out_point.clone(),
+        ScriptHashType::Data1,
+    )
+    .unwrap();
+    let tx = builder
+        .create(
+            std::slice::from_ref(&input),
+            &admin_lock,
+            &metadata,
+            false,
+            &options(&test_context, &admin_lock),
+        )
+        .unwrap();
+
+    let type_id_args = calc_type_id(&CellInput::new(input, 0), 0);
+    assert_eq!(
+        tx.outputs().get(0).unwrap().type_().to_opt(),
+        Some(type_id_script(&type_id_args))
+    );
+    assert_eq!(
+        builder.created_udt_type_script(&tx).unwrap(),
+        builder.udt_type_script(&type_id_args).unwrap()
+    );
+    assert_eq!(tx.outputs().len(), 2);
+    assert_eq!(tx.witnesses().len(), 1);
+    let json = to_ckb_cli_json(&tx);
+    assert!(json["transaction"]["outputs"].is_array());
+
+    let cycles = test_context
+        .context
+        .verify_tx(&tx, u64::MAX)
+        .expect("CLI Create Tx Failed");
+    println!("CLI Create Tx cycles: {}", cycles);
+}
+
+#[test]
+fn test_cli_create_immutable() {
+    let mut test_context = build_test_context();
+    let admin_lock = test_context.admin_lock_script.clone();
+    let input = capacity_cell(&mut test_context, &admin_lock);
+    // The format of `create --metadata`: fields after `icon` may be left out.
+    let metadata: SSRIMetadata = serde_json::from_value(json!({
+        "name": "Fixed UDT",
+        "symbol": "FIX",
+        "decimals": 6,
+        "icon": "",
+        "website": "https://example.com",
+    }))
+    .unwrap();
+    assert_eq!(metadata.website, "https://example.com");
+
+    let builder = UdtTxBuilder::new(
+        &test_context.context,
+        test_context.ssri_udt_out_point.clone(),
+        ScriptHashType::Data1,
+    )
+    .unwrap();
+    let tx = builder
+        .create(
+            std::slice::from_ref(&input),
+            &admin_lock,
+            &metadata,
+            true,
+            &options(&test_context, &admin_lock),
+        )
+        .unwrap();
+
+    let metadata_cell = tx.outputs().get(0).unwrap();
+    assert!(metadata_cell.type_().is_none());
+    let metadata_data = tx.outputs_data().get(0).unwrap().raw_data();
+    let udt_args = UDTArgs::immutable(admin_lock.calc_script_hash().unpack(), &metadata_data);
+    assert_eq!(
+        builder
+            .immutable_udt_type_script(&admin_lock, &metadata)
+            .unwrap(),
+        builder.udt_type_script(&udt_args.to_vec()).unwrap()
+    );
+
+    let cycles = test_context
+        .context
+        .verify_tx(&tx, u64::MAX)
+        .expect("CLI Create Immutable Tx Failed");
+    println!("CLI Create Immutable Tx cycles: {}", cycles);
+}
+
+#[test]
+fn test_cli_mint() {
+    let mut test_context = build_test_context();
+    let admin_lock = test_context.admin_lock_script.clone();
+    let input = capacity_cell(&mut test_context, &admin_lock);
+
+    let builder = UdtTxBuilder::new(
+        &test_context.context,
+        test_context.ssri_udt_out_point.clone(),
+        ScriptHashType::Type,
+    )
+    .unwrap();
+    let udt = builder.udt_type_script(&[0; 32]).unwrap();
+    assert_eq!(udt, test_context.ssri_udt_type_script);
+    let tx = builder
+        .mint(
+            &udt,
+            &[input],
+            &[(test_context.normal_user_b_lock_script.clone(), 500)],
+            &options(&test_context, &admin_lock),
+        )
+        .unwrap();
+
+    let cycles = test_context
+        .context
+        .verify_tx(&tx, u64::MAX)
+        .expect("CLI Mint Tx Failed");
+    println!("CLI Mint Tx cycles: {}", cycles);
+}
+
+#[test]
+fn test_cli_transfer() {
+    let mut test_context = build_test_context();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let input = udt_cell(&mut test_context, &user_a, 1000);
+
+    let builder = UdtTxBuilder::new(
+        &test_context.context,
+        test_context.ssri_udt_out_point.clone(),
+        ScriptHashType::Type,
+    )
+    .unwrap();
+    let udt = test_context.ssri_udt_type_script.clone();
+    let options = options(&test_context, &user_a);
+
+    let unbalanced = builder
+        .transfer(
+            &udt,
+            std::slice::from_ref(&input),
+            &[(user_b.clone(), 600)],
+            &options,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        unbalanced,
+        CliError::UnbalancedTransfer {
+            inputs: 1000,
+            outputs: 600
+        }
+    ));
+
+    let tx = builder
+        .transfer(
+            &udt,
+            &[input],
+            &[(user_b.clone(), 600), (user_a.clone(), 400)],
+            &options,
+        )
+        .unwrap();
+    assert_eq!(
+        tx.cell_deps().get(0).unwrap().out_point(),
+        test_context.ssri_udt_out_point
+    );
+    let cycles = test_context
+        .context
+        .verify_tx(&tx, u64::MAX)
+        .expect("CLI Transfer Tx Failed");
+    println!("CLI Transfer Tx cycles: {}", cycles);
+
+    let report = builder.inspect(&udt).unwrap();
+    assert_eq!(report["name"], "Test UDT");
+    assert_eq!(report["decimals"], 8);
+    assert_eq!(report["total"], "1000");
+}
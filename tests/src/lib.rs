@@ -11,9 +11,25 @@ use std::fs;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+#[cfg(test)]
+mod bench;
+#[cfg(test)]
+mod cli;
+#[cfg(test)]
+mod negative;
+#[cfg(test)]
+mod properties;
+#[cfg(test)]
+mod schema;
+#[cfg(test)]
+mod sdk;
 #[cfg(test)]
 mod ssri;
 #[cfg(test)]
+mod ssri_local;
+#[cfg(test)]
+mod ssri_server_local;
+#[cfg(test)]
 mod tests;
 #[cfg(test)]
 mod tool;
@@ -0,0 +1,240 @@
+// Transactions the UDT type script must reject. Each case asserts the exact
+// exit code, and the script group it is reported for, as the
+// `ScriptError::ValidationFailure` ckb-testtool returns.
+
+use ckb_std::ckb_types::{bytes::Bytes, packed::*, prelude::*};
+use ckb_testtool::{ckb_script::ScriptError, ckb_types::core::TransactionBuilder};
+use serde_molecule::to_vec;
+use ssri_udt_sdk::{error_code, SSRIMetadata, UDTArgs};
+
+use crate::utils::{assert_script_error, build_test_context};
+
+/// Metadata cell dep of a case.
+enum Metadata {
+    Missing,
+    /// The valid metadata cell of the test context.
+    Valid,
+    /// A metadata cell with this data instead.
+    Data(Bytes),
+}
+
+struct Case {
+    name: &'static str,
+    /// Adds an input locked by the metadata owner.
+    owner: bool,
+    metadata: Metadata,
+    /// UDT args replacing those of the test context.
+    args: Option<Vec<u8>>,
+    inputs: Vec<Bytes>,
+    outputs: Vec<Bytes>,
+    error: i8,
+}
+
+fn amount(amount: u128) -> Bytes {
+    amount.pack().as_bytes()
+}
+
+fn metadata_data(metadata: &SSRIMetadata) -> Bytes {
+    Bytes::from(to_vec(metadata, false).unwrap())
+}
+
+fn cases() -> Vec<Case> {
+    let metadata = SSRIMetadata {
+        name: String::from("Test UDT"),
+        symbol: String::from("TEST"),
+        decimals: 8,
+        icon: String::from("https://example.com/icon.png"),
+        ..Default::default()
+    };
+    vec![
+        Case {
+            name: "mint without the owner",
+            owner: false,
+            metadata: Metadata::Valid,
+            args: None,
+            inputs: vec![],
+            outputs: vec![amount(1000)],
+            error: error_code::NO_MINT_PERMISSION,
+        },
+        Case {
+            name: "output data shorter than an amount",
+            owner: false,
+            metadata: Metadata::Missing,
+            args: None,
+            inputs: vec![amount(1000)],
+            outputs: vec![amount(1000).slice(..15)],
+            error: error_code::ENCODING,
+        },
+        Case {
+            name: "input data longer than an amount",
+            owner: false,
+            metadata: Metadata::Missing,
+            args: None,
+            inputs: vec![Bytes::from([&amount(1000)[..], &[0]].concat())],
+            outputs: vec![amount(1000)],
+            error: error_code::ENCODING,
+        },
+        Case {
+            name: "mint without a metadata dep",
+            owner: true,
+            metadata: Metadata::Missing,
+            args: None,
+            inputs: vec![],
+            outputs: vec![amount(1000)],
+            error: error_code::SSRI_CONFIG_NOT_FOUND,
+        },
+        Case {
+            name: "mint with metadata that is not molecule",
+            owner: true,
+            metadata: Metadata::Data(Bytes::from_static(b"not metadata")),
+            args: None,
+            inputs: vec![],
+            outputs: vec![amount(1000)],
+            error: error_code::SSRI_CONFIG_INVALID_DATA_FORMAT,
+        },
+        Case {
+            name: "mint with metadata that breaks its rules",
+            owner: true,
+            metadata: Metadata::Data(metadata_data(&SSRIMetadata {
+                decimals: 40,
+                ..metadata.clone()
+            })),
+            args: None,
+            inputs: vec![],
+            outputs: vec![amount(1000)],
+            error: error_code::INVALID_METADATA_DECIMALS,
+        },
+        Case {
+            name: "mint with args of no known length",
+            owner: true,
+            metadata: Metadata::Valid,
+            args: Some(vec![0; 31]),
+            inputs: vec![],
+            outputs: vec![amount(1000)],
+            error: error_code::INVALID_UDT_ARGS,
+        },
+        Case {
+            name: "mint with the reserved singleton selector",
+            owner: true,
+            metadata: Metadata::Valid,
+            args: Some([&[1][..], &[0; 32]].concat()),
+            inputs: vec![],
+            outputs: vec![amount(1000)],
+            error: error_code::INVALID_UDT_ARGS,
+        },
+        Case {
+            name: "outputs below the inputs",
+            owner: false,
+            metadata: Metadata::Valid,
+            args: None,
+            inputs: vec![amount(1000)],
+            outputs: vec![amount(999)],
+            error: error_code::INSUFFICIENT_BALANCE,
+        },
+        Case {
+            name: "burn by the owner",
+            owner: true,
+            metadata: Metadata::Valid,
+            args: None,
+            inputs: vec![amount(1000)],
+            outputs: vec![],
+            error: error_code::INSUFFICIENT_BALANCE,
+        },
+        Case {
+            name: "inputs summing past u128::MAX",
+            owner: false,
+            metadata: Metadata::Missing,
+            args: None,
+            inputs: vec![amount(u128::MAX), amount(1)],
+            outputs: vec![amount(u128::MAX)],
+            error: error_code::AMOUNT_OVERFLOW,
+        },
+    ]
+}
+
+#[test]
+fn test_rejected_transactions() {
+    for case in cases() {
+        let mut test_context = build_test_context();
+        let type_script = match &case.args {
+            Some(args) => test_context
+                .ssri_udt_type_script
+                .clone()
+                .as_builder()
+                .args(args.pack())
+                .build(),
+            None => test_context.ssri_udt_type_script.clone(),
+        };
+        let udt_cell = |lock: &Script| {
+            CellOutput::new_builder()
+                .capacity(100u64.pack())
+                .lock(lock.clone())
+                .type_(Some(type_script.clone()).pack())
+                .build()
+        };
+
+        let plain_lock = if case.owner {
+            test_context.admin_lock_script.clone()
+        } else {
+            test_context.normal_user_a_lock_script.clone()
+        };
+        let plain_input = test_context.context.create_cell(
+            CellOutput::new_builder()
+                .capacity(10000u64.pack())
+                .lock(plain_lock)
+                .build(),
+            Bytes::default(),
+        );
+        let mut tx = TransactionBuilder::default()
+            .cell_deps(vec![
+                test_context.ssri_udt_dep.clone(),
+                test_context.always_success_dep.clone(),
+            ])
+            .input(
+                CellInput::new_builder()
+                    .previous_output(plain_input)
+                    .build(),
+            );
+        match &case.metadata {
+            Metadata::Missing => {}
+            Metadata::Valid => tx = tx.cell_dep(test_context.ssri_metadata_dep.clone()),
+            Metadata::Data(data) => {
+                let out_point = test_context.context.create_cell(
+                    CellOutput::new_builder()
+                        .capacity(1000u64.pack())
+                        .lock(test_context.admin_lock_script.clone())
+                        .type_(UDTArgs::TypeId([0; 32]).metadata_type_script().pack())
+                        .build(),
+                    data.clone(),
+                );
+                tx = tx.cell_dep(CellDep::new_builder().out_point(out_point).build());
+            }
+        }
+        for data in &case.inputs {
+            let out_point = test_context.context.create_cell(
+                udt_cell(&test_context.normal_user_a_lock_script),
+                data.clone(),
+            );
+            tx = tx.input(CellInput::new_builder().previous_output(out_point).build());
+        }
+        for data in &case.outputs {
+            tx = tx
+                .output(udt_cell(&test_context.normal_user_b_lock_script))
+                .output_data(data.pack());
+        }
+
+        let err = test_context
+            .context
+            .verify_tx(&tx.build(), u64::MAX)
+            .expect_err(case.name);
+        // The group is reported at its first input, after the plain one, or
+        // at its first output when it has no inputs.
+        let failure = ScriptError::validation_failure(&type_script, case.error);
+        let expected = if case.inputs.is_empty() {
+            failure.output_type_script(0)
+        } else {
+            failure.input_type_script(1)
+        };
+        assert_script_error(err, expected, case.name);
+    }
+}
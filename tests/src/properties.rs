@@ -0,0 +1,261 @@
+// Property tests for `fallback()`: random UDT inputs and outputs, with or
+// without an owner input and with the metadata cell in different places, are
+// verified with ckb-testtool and checked against what the contract promises:
+//
+// - without owner mode, a transaction only passes if it does not create UDT;
+// - with owner mode and reachable metadata, any increase is a valid mint;
+// - a UDT cell whose data is not 16 bytes fails the whole transaction.
+//
+// Burning is not supported, so an output total below the input total always
+// fails, owner or not.
+
+use std::cmp::Ordering;
+
+use ckb_std::ckb_types::{bytes::Bytes, packed::*, prelude::*};
+use ckb_testtool::ckb_types::core::TransactionBuilder;
+use proptest::prelude::*;
+use serde_molecule::to_vec;
+use ssri_udt_sdk::{error_code, SSRIMetadata, UDTArgs};
+
+use crate::utils::{build_test_context, PausableUDTTestContext};
+
+const CASES: u32 = 64;
+
+/// Where the metadata cell named by the UDT args sits in the transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Placement {
+    Missing,
+    CellDep,
+    /// Spent without being recreated.
+    Input,
+    /// Spent and recreated, as when updating the metadata.
+    Update,
+}
+
+impl Placement {
+    /// Spending the metadata cell needs its admin lock, which is owner mode.
+    fn spends_metadata(self) -> bool {
+        matches!(self, Self::Input | Self::Update)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Case {
+    inputs: Vec<u128>,
+    outputs: Vec<u128>,
+    owner: bool,
+    placement: Placement,
+}
+
+impl Case {
+    fn inputs_amount(&self) -> u128 {
+        self.inputs.iter().sum()
+    }
+
+    fn outputs_amount(&self) -> u128 {
+        self.outputs.iter().sum()
+    }
+
+    fn owner_mode(&self) -> bool {
+        self.owner || self.placement.spends_metadata()
+    }
+
+    /// Whether a mint in this case finds the metadata and the owner.
+    fn can_mint(&self) -> bool {
+        self.owner_mode() && self.placement != Placement::Missing
+    }
+}
+
+/// Placements that leave the admin lock out of the inputs.
+fn placement_without_owner() -> impl Strategy<Value = Placement> {
+    prop_oneof![Just(Placement::Missing), Just(Placement::CellDep)]
+}
+
+fn placement() -> impl Strategy<Value = Placement> {
+    prop_oneof![
+        Just(Placement::Missing),
+        Just(Placement::CellDep),
+        Just(Placement::Input),
+        Just(Placement::Update),
+    ]
+}
+
+/// Amounts stay below `u64::MAX` so no total can overflow.
+fn amounts(min_len: usize) -> impl Strategy<Value = Vec<u128>> {
+    prop::collection::vec(0..u64::MAX as u128, min_len..6)
+}
+
+fn case(
+    owner: impl Strategy<Value = bool>,
+    placement: impl Strategy<Value = Placement>,
+) -> impl Strategy<Value = Case> {
+    // At least one UDT output, so the type script always runs. Half of the
+    // cases redistribute the inputs exactly, as random totals rarely match.
+    (amounts(0), amounts(1), any::<bool>(), owner, placement).prop_map(
+        |(inputs, mut outputs, balanced, owner, placement)| {
+            if balanced {
+                let total = inputs.iter().sum::<u128>();
+                let rest = outputs[1..].iter().sum::<u128>();
+                match total.checked_sub(rest) {
+                    Some(first) => outputs[0] = first,
+                    None => outputs = vec![total],
+                }
+            }
+            Case {
+                inputs,
+                outputs,
+                owner,
+                placement,
+            }
+        },
+    )
+}
+
+/// Builds and verifies `case`. `corrupt` replaces the data of one UDT cell,
+/// counting inputs first and then outputs, and wraps around.
+fn verify(case: &Case, corrupt: Option<(usize, Bytes)>) -> Result<u64, String> {
+    let mut test_context = build_test_context();
+    let PausableUDTTestContext {
+        ssri_udt_type_script,
+        admin_lock_script,
+        normal_user_a_lock_script,
+        normal_user_b_lock_script,
+        ..
+    } = &test_context;
+    let udt_cell = |lock: &Script| {
+        CellOutput::new_builder()
+            .capacity(100u64.pack())
+            .lock(lock.clone())
+            .type_(Some(ssri_udt_type_script.clone()).pack())
+            .build()
+    };
+    let metadata_cell = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(admin_lock_script.clone())
+        .type_(UDTArgs::TypeId([0; 32]).metadata_type_script().pack())
+        .build();
+    let metadata_data = Bytes::from(to_vec(&metadata(), false).unwrap());
+    let plain_lock = if case.owner {
+        admin_lock_script.clone()
+    } else {
+        normal_user_a_lock_script.clone()
+    };
+    let udt_input = udt_cell(normal_user_a_lock_script);
+    let udt_output = udt_cell(normal_user_b_lock_script);
+
+    let mut data = case
+        .inputs
+        .iter()
+        .chain(&case.outputs)
+        .map(|amount| amount.pack().as_bytes())
+        .collect::<Vec<_>>();
+    if let Some((index, bytes)) = corrupt {
+        let index = index % data.len();
+        data[index] = bytes;
+    }
+    let (inputs_data, outputs_data) = data.split_at(case.inputs.len());
+
+    let plain_input = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(plain_lock)
+            .build(),
+        Bytes::default(),
+    );
+    let mut tx = TransactionBuilder::default()
+        .cell_deps(vec![
+            test_context.ssri_udt_dep.clone(),
+            test_context.always_success_dep.clone(),
+        ])
+        .input(
+            CellInput::new_builder()
+                .previous_output(plain_input)
+                .build(),
+        );
+    match case.placement {
+        Placement::Missing => {}
+        Placement::CellDep => tx = tx.cell_dep(test_context.ssri_metadata_dep.clone()),
+        Placement::Input | Placement::Update => {
+            let out_point = test_context
+                .context
+                .create_cell(metadata_cell.clone(), metadata_data.clone());
+            tx = tx.input(CellInput::new_builder().previous_output(out_point).build());
+        }
+    }
+    if case.placement == Placement::Update {
+        tx = tx.output(metadata_cell).output_data(metadata_data.pack());
+    }
+    for data in inputs_data {
+        let out_point = test_context
+            .context
+            .create_cell(udt_input.clone(), data.clone());
+        tx = tx.input(CellInput::new_builder().previous_output(out_point).build());
+    }
+    for data in outputs_data {
+        tx = tx.output(udt_output.clone()).output_data(data.pack());
+    }
+
+    test_context
+        .context
+        .verify_tx(&tx.build(), u64::MAX)
+        .map_err(|err| err.to_string())
+}
+
+fn metadata() -> SSRIMetadata {
+    SSRIMetadata {
+        name: String::from("Test UDT"),
+        symbol: String::from("TEST"),
+        decimals: 8,
+        icon: String::from("https://example.com/icon.png"),
+        ..Default::default()
+    }
+}
+
+fn assert_error_code(result: Result<u64, String>, code: i8) -> Result<(), TestCaseError> {
+    let err = result.expect_err("transaction should fail");
+    prop_assert!(err.contains(&format!("error code {} ", code)), "{}", err);
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn prop_transfer_never_creates_udt(case in case(Just(false), placement_without_owner())) {
+        let result = verify(&case, None);
+        let (inputs_amount, outputs_amount) = (case.inputs_amount(), case.outputs_amount());
+        if result.is_ok() {
+            prop_assert!(outputs_amount <= inputs_amount);
+        }
+        match inputs_amount.cmp(&outputs_amount) {
+            Ordering::Equal => prop_assert!(result.is_ok(), "{:?}", result),
+            Ordering::Greater => assert_error_code(result, error_code::INSUFFICIENT_BALANCE)?,
+            Ordering::Less if case.placement == Placement::Missing => {
+                assert_error_code(result, error_code::SSRI_CONFIG_NOT_FOUND)?
+            }
+            Ordering::Less => assert_error_code(result, error_code::NO_MINT_PERMISSION)?,
+        }
+    }
+
+    #[test]
+    fn prop_owner_mints(case in case(Just(true), placement())) {
+        let result = verify(&case, None);
+        match case.inputs_amount().cmp(&case.outputs_amount()) {
+            Ordering::Equal => prop_assert!(result.is_ok(), "{:?}", result),
+            // Burning is not supported.
+            Ordering::Greater => assert_error_code(result, error_code::INSUFFICIENT_BALANCE)?,
+            Ordering::Less if case.can_mint() => prop_assert!(result.is_ok(), "{:?}", result),
+            Ordering::Less => assert_error_code(result, error_code::SSRI_CONFIG_NOT_FOUND)?,
+        }
+    }
+
+    #[test]
+    fn prop_wrong_data_length_fails(
+        case in case(any::<bool>(), placement()),
+        index in any::<usize>(),
+        len in (0..48usize).prop_filter("not an amount", |len| *len != 16),
+    ) {
+        let result = verify(&case, Some((index, Bytes::from(vec![0; len]))));
+        assert_error_code(result, error_code::ENCODING)?;
+    }
+}
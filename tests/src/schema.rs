@@ -0,0 +1,585 @@
+// NOTE: Every vector in schemas/vectors.json is encoded three ways (the hex in the file, serde_molecule through ssri-udt-sdk, and code generated from schemas/ssri_udt.mol) and all three must agree byte-for-byte.
+
+use ckb_ssri_std::prelude::encode_u64_vector;
+use ckb_std::ckb_types::{
+    bytes::Bytes,
+    packed::{OutPoint, Script},
+    prelude::*,
+};
+use molecule::prelude::{Builder, Byte, Entity, Reader};
+use serde_json::Value;
+use serde_molecule::{from_slice, to_vec};
+use ssri_udt_sdk::{
+    decode_amount_vec, encode_amount_vec, CreatedScripts, IconDataRef, IconRef, LocalizedText,
+    LockDelta, MetadataRecord, SSRIMetadata, SSRIMetadataV1, SSRIMetadataV2, ScriptLikeVec,
+    TransactionReport, VerifyResult,
+};
+
+#[allow(clippy::all, dead_code)]
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/ssri_udt.rs"));
+}
+
+const VECTORS: &str = include_str!("../../schemas/vectors.json");
+
+fn decode_hex_str(hex: &str) -> Vec<u8> {
+    let hex = hex.strip_prefix("0x").expect("0x prefix");
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn gen_bytes(data: &[u8]) -> generated::Bytes {
+    generated::Bytes::new_builder()
+        .extend(data.iter().copied().map(Byte::new))
+        .build()
+}
+
+fn gen_uint128(amount: u128) -> generated::Uint128 {
+    generated::Uint128::new_builder()
+        .set(amount.to_le_bytes().map(Byte::new))
+        .build()
+}
+
+fn u128_field(value: &Value, field: &str) -> u128 {
+    str_field(value, field).parse().unwrap()
+}
+
+fn str_field<'a>(value: &'a Value, field: &str) -> &'a str {
+    value[field].as_str().unwrap()
+}
+
+fn vectors_of(type_name: &str) -> Vec<(String, Value, Vec<u8>)> {
+    let vectors: Vec<Value> = serde_json::from_str(VECTORS).unwrap();
+    let vectors = vectors
+        .into_iter()
+        .filter(|vector| vector["type"] == type_name)
+        .map(|vector| {
+            (
+                str_field(&vector, "description").to_string(),
+                vector["value"].clone(),
+                decode_hex_str(str_field(&vector, "hex")),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert!(!vectors.is_empty(), "No vectors for {}", type_name);
+    vectors
+}
+
+#[test]
+fn test_uint128_vec_vectors() {
+    for (description, value, expected) in vectors_of("Uint128Vec") {
+        let amounts = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|amount| amount.as_str().unwrap().parse::<u128>().unwrap())
+            .collect::<Vec<_>>();
+
+        let generated = generated::Uint128Vec::new_builder()
+            .extend(amounts.iter().copied().map(gen_uint128))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        assert_eq!(encode_amount_vec(&amounts), expected, "{}", description);
+        assert_eq!(decode_amount_vec(&expected).unwrap(), amounts);
+    }
+}
+
+#[test]
+fn test_uint64_vec_vectors() {
+    for (description, value, expected) in vectors_of("Uint64Vec") {
+        let paths = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|path| path.as_str().unwrap().parse::<u64>().unwrap())
+            .collect::<Vec<_>>();
+
+        let generated = generated::Uint64Vec::new_builder()
+            .extend(paths.iter().map(|path| {
+                generated::Uint64::new_builder()
+                    .set(path.to_le_bytes().map(Byte::new))
+                    .build()
+            }))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        // SSRI.has_methods arguments are produced by ckb-ssri-std, not serde_molecule.
+        assert_eq!(encode_u64_vector(&paths), expected, "{}", description);
+        assert_eq!(to_vec(&paths, false).unwrap(), expected, "{}", description);
+    }
+}
+
+#[test]
+fn test_script_like_vec_vectors() {
+    for (description, value, expected) in vectors_of("ScriptLikeVec") {
+        let scripts = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|script| {
+                let code_hash: [u8; 32] = decode_hex_str(str_field(script, "code_hash"))
+                    .try_into()
+                    .unwrap();
+                let hash_type = decode_hex_str(str_field(script, "hash_type"))[0];
+                let args = decode_hex_str(str_field(script, "args"));
+                (code_hash, hash_type, args)
+            })
+            .collect::<Vec<_>>();
+
+        let generated = generated::ScriptLikeVec::new_builder()
+            .extend(scripts.iter().map(|(code_hash, hash_type, args)| {
+                generated::ScriptLike::new_builder()
+                    .code_hash(
+                        generated::Byte32::new_builder()
+                            .set(code_hash.map(Byte::new))
+                            .build(),
+                    )
+                    .hash_type(Byte::new(*hash_type))
+                    .args(gen_bytes(args))
+                    .build()
+            }))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+
+        let packed_scripts = scripts
+            .iter()
+            .map(|(code_hash, hash_type, args)| {
+                Script::new_builder()
+                    .code_hash(code_hash.pack())
+                    .hash_type(Byte::new(*hash_type))
+                    .args(Bytes::from(args.clone()).pack())
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        let encoded = ScriptLikeVec::from_scripts(&packed_scripts)
+            .encode()
+            .unwrap();
+        assert_eq!(encoded, expected, "{}", description);
+        assert_eq!(
+            ScriptLikeVec::decode(&expected).unwrap().into_scripts(),
+            packed_scripts
+        );
+    }
+}
+
+fn script_of(value: &Value) -> Script {
+    let code_hash: [u8; 32] = decode_hex_str(str_field(value, "code_hash"))
+        .try_into()
+        .unwrap();
+    Script::new_builder()
+        .code_hash(code_hash.pack())
+        .hash_type(Byte::new(decode_hex_str(str_field(value, "hash_type"))[0]))
+        .args(Bytes::from(decode_hex_str(str_field(value, "args"))).pack())
+        .build()
+}
+
+fn gen_script(script: &Script) -> generated::ScriptLike {
+    generated::ScriptLike::new_builder()
+        .code_hash(
+            generated::Byte32::new_builder()
+                .set(Unpack::<[u8; 32]>::unpack(&script.code_hash()).map(Byte::new))
+                .build(),
+        )
+        .hash_type(script.hash_type())
+        .args(gen_bytes(&script.args().raw_data()))
+        .build()
+}
+
+#[test]
+fn test_created_scripts_vectors() {
+    for (description, value, expected) in vectors_of("CreatedScripts") {
+        let metadata_type_script = script_of(&value["metadata_type_script"]);
+        let udt_type_script = script_of(&value["udt_type_script"]);
+
+        let generated = generated::CreatedScripts::new_builder()
+            .metadata_type_script(gen_script(&metadata_type_script))
+            .udt_type_script(gen_script(&udt_type_script))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        let created = CreatedScripts::new(&metadata_type_script, &udt_type_script);
+        assert_eq!(created.encode().unwrap(), expected, "{}", description);
+        assert_eq!(
+            CreatedScripts::decode(&expected).unwrap().into_scripts(),
+            (metadata_type_script, udt_type_script)
+        );
+    }
+}
+
+fn metadata_of(value: &Value) -> SSRIMetadata {
+    let icon_ref = match &value["icon_ref"] {
+        Value::Null => None,
+        icon_ref if icon_ref.get("type_id").is_some() => Some(IconRef::TypeId(
+            decode_hex_str(str_field(icon_ref, "type_id"))
+                .try_into()
+                .unwrap(),
+        )),
+        icon_ref => Some(IconRef::DataHash(IconDataRef {
+            tx_hash: decode_hex_str(str_field(icon_ref, "tx_hash"))
+                .try_into()
+                .unwrap(),
+            index: icon_ref["index"].as_u64().unwrap() as u32,
+            data_hash: decode_hex_str(str_field(icon_ref, "data_hash"))
+                .try_into()
+                .unwrap(),
+        })),
+    };
+    SSRIMetadata {
+        name: str_field(value, "name").to_string(),
+        symbol: str_field(value, "symbol").to_string(),
+        decimals: value["decimals"].as_u64().unwrap() as u8,
+        icon: str_field(value, "icon").to_string(),
+        version: value["version"].as_u64().unwrap() as u8,
+        description: str_field(value, "description").to_string(),
+        website: str_field(value, "website").to_string(),
+        social_links: value["social_links"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|link| link.as_str().unwrap().to_string())
+            .collect(),
+        category: str_field(value, "category").to_string(),
+        extra: value["extra"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .map(|(key, value)| (key.clone(), decode_hex_str(value.as_str().unwrap())))
+            .collect(),
+        icon_ref,
+        localized: value
+            .get("localized")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .map(|(locale, text)| {
+                (
+                    locale.clone(),
+                    LocalizedText {
+                        name: str_field(text, "name").to_string(),
+                        description: str_field(text, "description").to_string(),
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
+fn gen_social_links(metadata: &SSRIMetadata) -> generated::BytesVec {
+    generated::BytesVec::new_builder()
+        .extend(
+            metadata
+                .social_links
+                .iter()
+                .map(|link| gen_bytes(link.as_bytes())),
+        )
+        .build()
+}
+
+fn gen_extra(metadata: &SSRIMetadata) -> generated::ExtraEntryVec {
+    generated::ExtraEntryVec::new_builder()
+        .extend(metadata.extra.iter().map(|(key, value)| {
+            generated::ExtraEntry::new_builder()
+                .key(gen_bytes(key.as_bytes()))
+                .value(gen_bytes(value))
+                .build()
+        }))
+        .build()
+}
+
+fn gen_localized(metadata: &SSRIMetadata) -> generated::LocaleEntryVec {
+    generated::LocaleEntryVec::new_builder()
+        .extend(metadata.localized.iter().map(|(locale, text)| {
+            generated::LocaleEntry::new_builder()
+                .key(gen_bytes(locale.as_bytes()))
+                .value(
+                    generated::LocalizedText::new_builder()
+                        .name(gen_bytes(text.name.as_bytes()))
+                        .description(gen_bytes(text.description.as_bytes()))
+                        .build(),
+                )
+                .build()
+        }))
+        .build()
+}
+
+fn gen_byte32(data: &[u8; 32]) -> generated::Byte32 {
+    generated::Byte32::new_builder()
+        .set(data.map(Byte::new))
+        .build()
+}
+
+fn gen_icon_ref(icon_ref: &Option<IconRef>) -> generated::IconRefOpt {
+    let icon_ref = icon_ref.as_ref().map(|icon_ref| {
+        let item: generated::IconRefUnion = match icon_ref {
+            IconRef::TypeId(type_id_args) => gen_byte32(type_id_args).into(),
+            IconRef::DataHash(data_ref) => generated::IconDataRef::new_builder()
+                .tx_hash(gen_byte32(&data_ref.tx_hash))
+                .index(
+                    generated::Uint32::new_builder()
+                        .set(data_ref.index.to_le_bytes().map(Byte::new))
+                        .build(),
+                )
+                .data_hash(gen_byte32(&data_ref.data_hash))
+                .build()
+                .into(),
+        };
+        generated::IconRef::new_builder().set(item).build()
+    });
+    generated::IconRefOpt::new_builder().set(icon_ref).build()
+}
+
+fn gen_metadata(metadata: &SSRIMetadata) -> generated::SSRIMetadata {
+    generated::SSRIMetadata::new_builder()
+        .name(gen_bytes(metadata.name.as_bytes()))
+        .symbol(gen_bytes(metadata.symbol.as_bytes()))
+        .decimals(Byte::new(metadata.decimals))
+        .icon(gen_bytes(metadata.icon.as_bytes()))
+        .version(Byte::new(metadata.version))
+        .description(gen_bytes(metadata.description.as_bytes()))
+        .website(gen_bytes(metadata.website.as_bytes()))
+        .social_links(gen_social_links(metadata))
+        .category(gen_bytes(metadata.category.as_bytes()))
+        .extra(gen_extra(metadata))
+        .icon_ref(gen_icon_ref(&metadata.icon_ref))
+        .localized(gen_localized(metadata))
+        .build()
+}
+
+#[test]
+fn test_ssri_metadata_vectors() {
+    for (description, value, expected) in vectors_of("SSRIMetadata") {
+        let metadata = metadata_of(&value);
+
+        let generated = gen_metadata(&metadata);
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        assert_eq!(metadata.encode().unwrap(), expected, "{}", description);
+        assert_eq!(
+            to_vec(&metadata, false).unwrap(),
+            expected,
+            "{}",
+            description
+        );
+        assert_eq!(SSRIMetadata::decode(&expected).unwrap(), metadata);
+        generated::SSRIMetadataReader::verify(&expected, false).unwrap();
+    }
+}
+
+#[test]
+fn test_metadata_record_vectors() {
+    for (description, value, expected) in vectors_of("MetadataRecord") {
+        let metadata = metadata_of(&value["metadata"]);
+        let tx_hash: [u8; 32] = decode_hex_str(str_field(&value, "tx_hash"))
+            .try_into()
+            .unwrap();
+        let out_point = OutPoint::new_builder()
+            .tx_hash(tx_hash.pack())
+            .index((value["index"].as_u64().unwrap() as u32).pack())
+            .build();
+        let owner_lock = script_of(&value["owner_lock"]);
+
+        let generated = generated::MetadataRecord::new_builder()
+            .metadata(gen_metadata(&metadata))
+            .tx_hash(gen_byte32(&tx_hash))
+            .index(
+                generated::Uint32::new_builder()
+                    .set(
+                        Unpack::<u32>::unpack(&out_point.index())
+                            .to_le_bytes()
+                            .map(Byte::new),
+                    )
+                    .build(),
+            )
+            .owner_lock(gen_script(&owner_lock))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        let record = MetadataRecord::new(metadata.clone(), &out_point, &owner_lock);
+        assert_eq!(record.encode().unwrap(), expected, "{}", description);
+        let record = MetadataRecord::decode(&expected).unwrap();
+        assert_eq!(record.metadata, metadata);
+        assert_eq!(record.out_point(), out_point);
+        assert_eq!(record.owner_lock(), owner_lock);
+    }
+}
+
+#[test]
+fn test_ssri_metadata_v4_vectors() {
+    for (description, value, expected) in vectors_of("SSRIMetadataV4") {
+        let metadata = metadata_of(&value);
+
+        let generated = generated::SSRIMetadataV4::new_builder()
+            .name(gen_bytes(metadata.name.as_bytes()))
+            .symbol(gen_bytes(metadata.symbol.as_bytes()))
+            .decimals(Byte::new(metadata.decimals))
+            .icon(gen_bytes(metadata.icon.as_bytes()))
+            .version(Byte::new(metadata.version))
+            .description(gen_bytes(metadata.description.as_bytes()))
+            .website(gen_bytes(metadata.website.as_bytes()))
+            .social_links(gen_social_links(&metadata))
+            .category(gen_bytes(metadata.category.as_bytes()))
+            .extra(gen_extra(&metadata))
+            .icon_ref(gen_icon_ref(&metadata.icon_ref))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        let decoded = SSRIMetadata::decode(&expected).unwrap();
+        assert_eq!(decoded, metadata, "{}", description);
+        assert_eq!(decoded.version, 4);
+        generated::SSRIMetadataV4Reader::verify(&expected, false).unwrap();
+    }
+}
+
+#[test]
+fn test_ssri_metadata_v3_vectors() {
+    for (description, value, expected) in vectors_of("SSRIMetadataV3") {
+        let metadata = metadata_of(&value);
+
+        let generated = generated::SSRIMetadataV3::new_builder()
+            .name(gen_bytes(metadata.name.as_bytes()))
+            .symbol(gen_bytes(metadata.symbol.as_bytes()))
+            .decimals(Byte::new(metadata.decimals))
+            .icon(gen_bytes(metadata.icon.as_bytes()))
+            .version(Byte::new(metadata.version))
+            .description(gen_bytes(metadata.description.as_bytes()))
+            .website(gen_bytes(metadata.website.as_bytes()))
+            .social_links(gen_social_links(&metadata))
+            .category(gen_bytes(metadata.category.as_bytes()))
+            .extra(gen_extra(&metadata))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        let decoded = SSRIMetadata::decode(&expected).unwrap();
+        assert_eq!(decoded, metadata, "{}", description);
+        assert_eq!(decoded.version, 3);
+        generated::SSRIMetadataV3Reader::verify(&expected, false).unwrap();
+    }
+}
+
+#[test]
+fn test_ssri_metadata_v2_vectors() {
+    for (description, value, expected) in vectors_of("SSRIMetadataV2") {
+        let v2 = SSRIMetadataV2 {
+            name: str_field(&value, "name").to_string(),
+            symbol: str_field(&value, "symbol").to_string(),
+            decimals: value["decimals"].as_u64().unwrap() as u8,
+            icon: str_field(&value, "icon").to_string(),
+            version: value["version"].as_u64().unwrap() as u8,
+        };
+
+        let generated = generated::SSRIMetadataV2::new_builder()
+            .name(gen_bytes(v2.name.as_bytes()))
+            .symbol(gen_bytes(v2.symbol.as_bytes()))
+            .decimals(Byte::new(v2.decimals))
+            .icon(gen_bytes(v2.icon.as_bytes()))
+            .version(Byte::new(v2.version))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        assert_eq!(to_vec(&v2, false).unwrap(), expected, "{}", description);
+        assert_eq!(from_slice::<SSRIMetadataV2>(&expected, false).unwrap(), v2);
+        let decoded = SSRIMetadata::decode(&expected).unwrap();
+        assert_eq!(decoded, SSRIMetadata::from(v2), "{}", description);
+        assert_eq!(decoded.version, 2);
+        assert!(decoded.social_links.is_empty() && decoded.extra.is_empty());
+        generated::SSRIMetadataV2Reader::verify(&expected, false).unwrap();
+    }
+}
+
+#[test]
+fn test_ssri_metadata_v1_vectors() {
+    for (description, value, expected) in vectors_of("SSRIMetadataV1") {
+        let v1 = SSRIMetadataV1 {
+            name: str_field(&value, "name").to_string(),
+            symbol: str_field(&value, "symbol").to_string(),
+            decimals: value["decimals"].as_u64().unwrap() as u8,
+            icon: str_field(&value, "icon").to_string(),
+        };
+
+        let generated = generated::SSRIMetadataV1::new_builder()
+            .name(gen_bytes(v1.name.as_bytes()))
+            .symbol(gen_bytes(v1.symbol.as_bytes()))
+            .decimals(Byte::new(v1.decimals))
+            .icon(gen_bytes(v1.icon.as_bytes()))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        assert_eq!(to_vec(&v1, false).unwrap(), expected, "{}", description);
+        assert_eq!(from_slice::<SSRIMetadataV1>(&expected, false).unwrap(), v1);
+        let decoded = SSRIMetadata::decode(&expected).unwrap();
+        assert_eq!(decoded, SSRIMetadata::from(v1), "{}", description);
+        assert_eq!(decoded.version, 1);
+        generated::SSRIMetadataV1Reader::verify(&expected, false).unwrap();
+    }
+}
+
+#[test]
+fn test_transaction_report_vectors() {
+    for (description, value, expected) in vectors_of("TransactionReport") {
+        let report = TransactionReport {
+            lock_deltas: value["lock_deltas"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|delta| LockDelta {
+                    lock_hash: decode_hex_str(str_field(delta, "lock_hash"))
+                        .try_into()
+                        .unwrap(),
+                    input_amount: u128_field(delta, "input_amount"),
+                    output_amount: u128_field(delta, "output_amount"),
+                })
+                .collect(),
+            input_amount: u128_field(&value, "input_amount"),
+            output_amount: u128_field(&value, "output_amount"),
+            minted: u128_field(&value, "minted"),
+            burned: u128_field(&value, "burned"),
+            transferred: u128_field(&value, "transferred"),
+            owner_mode: value["owner_mode"].as_bool().unwrap(),
+            path: value["path"].as_u64().unwrap() as u8,
+        };
+
+        let generated = generated::TransactionReport::new_builder()
+            .lock_deltas(
+                generated::LockDeltaVec::new_builder()
+                    .extend(report.lock_deltas.iter().map(|delta| {
+                        generated::LockDelta::new_builder()
+                            .lock_hash(
+                                generated::Byte32::new_builder()
+                                    .set(delta.lock_hash.map(Byte::new))
+                                    .build(),
+                            )
+                            .input_amount(gen_uint128(delta.input_amount))
+                            .output_amount(gen_uint128(delta.output_amount))
+                            .build()
+                    }))
+                    .build(),
+            )
+            .input_amount(gen_uint128(report.input_amount))
+            .output_amount(gen_uint128(report.output_amount))
+            .minted(gen_uint128(report.minted))
+            .burned(gen_uint128(report.burned))
+            .transferred(gen_uint128(report.transferred))
+            .owner_mode(Byte::new(report.owner_mode as u8))
+            .path(Byte::new(report.path))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        assert_eq!(report.encode().unwrap(), expected, "{}", description);
+        assert_eq!(TransactionReport::decode(&expected).unwrap(), report);
+        generated::TransactionReportReader::verify(&expected, false).unwrap();
+    }
+}
+
+#[test]
+fn test_verify_result_vectors() {
+    for (description, value, expected) in vectors_of("VerifyResult") {
+        let result = VerifyResult {
+            passed: value["passed"].as_bool().unwrap(),
+            error_code: value["error_code"].as_u64().unwrap() as u8,
+            error: str_field(&value, "error").to_string(),
+        };
+
+        let generated = generated::VerifyResult::new_builder()
+            .passed(Byte::new(result.passed as u8))
+            .error_code(Byte::new(result.error_code))
+            .error(gen_bytes(result.error.as_bytes()))
+            .build();
+        assert_eq!(generated.as_slice(), expected, "{}", description);
+        assert_eq!(result.encode().unwrap(), expected, "{}", description);
+        assert_eq!(VerifyResult::decode(&expected).unwrap(), result);
+        generated::VerifyResultReader::verify(&expected, false).unwrap();
+    }
+}
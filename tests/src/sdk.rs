@@ -0,0 +1,404 @@
+use ckb_std::ckb_types::{bytes::Bytes, core::ScriptHashType, packed::*, prelude::*};
+use ckb_testtool::ckb_types::core::TransactionBuilder;
+use serde_molecule::to_vec;
+use ssri_udt_sdk::{
+    decode::{arg, decode_flag, decode_hex, decode_optional_tx, decode_recipients, decode_u32},
+    decode_amount_vec, encode_amount_vec,
+    metadata::{
+        MAX_CATEGORY_LEN, MAX_DECIMALS, MAX_DESCRIPTION_LEN, MAX_EXTRA_ENTRIES,
+        MAX_EXTRA_VALUE_LEN, MAX_ICON_LEN, MAX_LINK_LEN, MAX_LOCALES, MAX_NAME_LEN,
+        MAX_SOCIAL_LINKS, MAX_SYMBOL_LEN,
+    },
+    singleton::{
+        UNIQUE_CELL_CODE_HASH, UNIQUE_CELL_HASH_TYPE, UNIQUE_CELL_MAINNET_CODE_HASH,
+        UNIQUE_CELL_TESTNET_CODE_HASH,
+    },
+    type_id::{calc_type_id, type_id_script, TYPE_ID_SCRIPT_CODE_HASH},
+    ArgError, IconRef, LocalizedText, MetadataError, MetadataSingleton, SSRIMetadata,
+    ScriptLikeVec, UDTArgs,
+};
+
+use crate::utils::build_test_context;
+
+#[test]
+fn test_amount_vec_layout() {
+    let encoded = encode_amount_vec(&[1, u128::MAX]);
+    let mut expected = 2u32.to_le_bytes().to_vec();
+    expected.extend_from_slice(&1u128.to_le_bytes());
+    expected.extend_from_slice(&u128::MAX.to_le_bytes());
+    assert_eq!(encoded, expected);
+    assert_eq!(decode_amount_vec(&encoded).unwrap(), vec![1, u128::MAX]);
+    assert!(decode_amount_vec(&encoded[..encoded.len() - 1]).is_err());
+}
+
+#[test]
+fn test_script_like_vec_roundtrip() {
+    let test_context = build_test_context();
+    let scripts = vec![
+        test_context.normal_user_a_lock_script.clone(),
+        test_context.ssri_udt_type_script.clone(),
+    ];
+    let encoded = ScriptLikeVec::from_scripts(&scripts).encode().unwrap();
+    let decoded = ScriptLikeVec::decode(&encoded).unwrap().into_scripts();
+    assert_eq!(decoded, scripts);
+}
+
+#[test]
+fn test_argument_decoders() {
+    let hex = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    };
+    assert_eq!(decode_hex(b"00aBfF").unwrap(), vec![0x00, 0xab, 0xff]);
+    assert_eq!(decode_hex(b"abc"), Err(ArgError::Encoding));
+    assert_eq!(decode_hex(b"0g"), Err(ArgError::Encoding));
+    // Not UTF-8, which `ckb_std::high_level::decode_hex` would panic on.
+    assert_eq!(decode_hex(&[0xff, 0xfe]), Err(ArgError::Encoding));
+
+    let argv: [&[u8]; 2] = [b"", b"00"];
+    assert_eq!(arg(&argv, 1), Ok(&b"00"[..]));
+    assert_eq!(arg(&argv, 2), Err(ArgError::Invalid));
+    assert!(decode_optional_tx(b"").unwrap().is_none());
+    assert_eq!(decode_flag(None), Ok(false));
+    assert_eq!(decode_flag(Some(b"01")), Ok(true));
+    assert_eq!(decode_flag(Some(b"02")), Err(ArgError::Invalid));
+    assert_eq!(decode_u32(b"0a000000"), Ok(10));
+    assert_eq!(decode_u32(b"0a00"), Err(ArgError::Invalid));
+
+    let locks = hex(
+        &ScriptLikeVec::from_scripts(&[Script::default(), Script::default()])
+            .encode()
+            .unwrap(),
+    );
+    let two_amounts = hex(&encode_amount_vec(&[1, 2]));
+    let one_amount = hex(&encode_amount_vec(&[1]));
+    let (to_lock_vec, to_amount_vec) =
+        decode_recipients(locks.as_bytes(), two_amounts.as_bytes()).unwrap();
+    assert_eq!(to_lock_vec, vec![Script::default(), Script::default()]);
+    assert_eq!(to_amount_vec, vec![1, 2]);
+    assert_eq!(
+        decode_recipients(locks.as_bytes(), one_amount.as_bytes()),
+        Err(ArgError::Invalid)
+    );
+    assert_eq!(
+        decode_recipients(locks.as_bytes(), &two_amounts.as_bytes()[2..]),
+        Err(ArgError::Molecule)
+    );
+    assert_eq!(
+        decode_recipients(b"", two_amounts.as_bytes()),
+        Err(ArgError::Invalid)
+    );
+    assert_eq!(
+        decode_recipients(locks.as_bytes(), b""),
+        Err(ArgError::Invalid)
+    );
+    assert_eq!(
+        decode_recipients(
+            hex(&ScriptLikeVec::from_scripts(&[]).encode().unwrap()).as_bytes(),
+            hex(&encode_amount_vec(&[])).as_bytes()
+        ),
+        Err(ArgError::Invalid)
+    );
+}
+
+#[test]
+fn test_type_id_accepted_by_type_id_script() {
+    let mut test_context = build_test_context();
+
+    let input_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let input = CellInput::new_builder()
+        .previous_output(input_out_point)
+        .build();
+
+    let metadata = SSRIMetadata {
+        name: String::from("Created UDT"),
+        symbol: String::from("CRT"),
+        decimals: 6,
+        icon: String::from("https://example.com/created.png"),
+        ..Default::default()
+    };
+    let metadata_output = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(test_context.admin_lock_script.clone())
+        .type_(Some(type_id_script(&calc_type_id(&input, 0))).pack())
+        .build();
+
+    let create_tx = TransactionBuilder::default()
+        .input(input)
+        .output(metadata_output)
+        .output_data(Bytes::from(to_vec(&metadata, false).unwrap()).pack())
+        .cell_dep(test_context.always_success_dep.clone())
+        .build();
+
+    let cycles = test_context
+        .context
+        .verify_tx(&create_tx, u64::MAX)
+        .expect("Type ID create Tx Failed");
+    println!("Type ID create Tx cycles: {}", cycles);
+}
+
+#[test]
+fn test_metadata_validation() {
+    let valid = SSRIMetadata {
+        name: String::from("Test UDT"),
+        symbol: String::from("TEST-1.a_b"),
+        decimals: MAX_DECIMALS,
+        icon: String::from("data:image/png;base64,iVBORw0KGgo="),
+        website: String::from("https://example.com"),
+        social_links: vec![String::from("https://x.com/example")],
+        extra: [(String::from("audit"), vec![1, 2, 3])].into(),
+        ..Default::default()
+    };
+    assert_eq!(valid.validate(), Ok(()));
+    let no_icon = SSRIMetadata {
+        icon: String::new(),
+        ..valid.clone()
+    };
+    assert_eq!(no_icon.validate(), Ok(()));
+    let icon_ref = SSRIMetadata {
+        icon_ref: Some(IconRef::TypeId([0; 32])),
+        ..no_icon.clone()
+    };
+    assert_eq!(icon_ref.validate(), Ok(()));
+
+    let cases = [
+        (
+            SSRIMetadata {
+                decimals: MAX_DECIMALS + 1,
+                ..valid.clone()
+            },
+            MetadataError::InvalidDecimals,
+        ),
+        (
+            SSRIMetadata {
+                name: String::new(),
+                ..valid.clone()
+            },
+            MetadataError::EmptyName,
+        ),
+        (
+            SSRIMetadata {
+                name: "名".repeat(MAX_NAME_LEN / 3 + 1),
+                ..valid.clone()
+            },
+            MetadataError::NameTooLong,
+        ),
+        (
+            SSRIMetadata {
+                symbol: String::new(),
+                ..valid.clone()
+            },
+            MetadataError::EmptySymbol,
+        ),
+        (
+            SSRIMetadata {
+                symbol: "T".repeat(MAX_SYMBOL_LEN + 1),
+                ..valid.clone()
+            },
+            MetadataError::SymbolTooLong,
+        ),
+        (
+            SSRIMetadata {
+                symbol: String::from("ÜDT"),
+                ..valid.clone()
+            },
+            MetadataError::InvalidSymbolCharacter,
+        ),
+        (
+            SSRIMetadata {
+                icon: String::from("http://example.com/icon.png"),
+                ..valid.clone()
+            },
+            MetadataError::InvalidIconScheme,
+        ),
+        (
+            SSRIMetadata {
+                icon: format!("https://{}", "a".repeat(MAX_ICON_LEN)),
+                ..valid.clone()
+            },
+            MetadataError::IconTooLarge,
+        ),
+        (
+            SSRIMetadata {
+                description: "d".repeat(MAX_DESCRIPTION_LEN + 1),
+                ..valid.clone()
+            },
+            MetadataError::DescriptionTooLong,
+        ),
+        (
+            SSRIMetadata {
+                website: String::from("http://example.com"),
+                ..valid.clone()
+            },
+            MetadataError::InvalidLink,
+        ),
+        (
+            SSRIMetadata {
+                social_links: vec![format!("https://{}", "a".repeat(MAX_LINK_LEN))],
+                ..valid.clone()
+            },
+            MetadataError::InvalidLink,
+        ),
+        (
+            SSRIMetadata {
+                social_links: vec![String::from("https://x.com/example"); MAX_SOCIAL_LINKS + 1],
+                ..valid.clone()
+            },
+            MetadataError::TooManySocialLinks,
+        ),
+        (
+            SSRIMetadata {
+                category: "c".repeat(MAX_CATEGORY_LEN + 1),
+                ..valid.clone()
+            },
+            MetadataError::CategoryTooLong,
+        ),
+        (
+            SSRIMetadata {
+                extra: (0..=MAX_EXTRA_ENTRIES)
+                    .map(|i| (i.to_string(), Vec::new()))
+                    .collect(),
+                ..valid.clone()
+            },
+            MetadataError::ExtraTooLarge,
+        ),
+        (
+            SSRIMetadata {
+                extra: [(String::from("audit"), vec![0; MAX_EXTRA_VALUE_LEN + 1])].into(),
+                ..valid.clone()
+            },
+            MetadataError::ExtraTooLarge,
+        ),
+        (
+            SSRIMetadata {
+                icon_ref: Some(IconRef::TypeId([0; 32])),
+                ..valid.clone()
+            },
+            MetadataError::AmbiguousIcon,
+        ),
+        (
+            SSRIMetadata {
+                localized: [(String::from("zh_CN"), LocalizedText::default())].into(),
+                ..valid.clone()
+            },
+            MetadataError::InvalidLocale,
+        ),
+        (
+            SSRIMetadata {
+                localized: (0..=MAX_LOCALES)
+                    .map(|i| (format!("x-{}", i), LocalizedText::default()))
+                    .collect(),
+                ..valid.clone()
+            },
+            MetadataError::TooManyLocales,
+        ),
+        (
+            SSRIMetadata {
+                localized: [(
+                    String::from("zh"),
+                    LocalizedText {
+                        name: "名".repeat(MAX_NAME_LEN / 3 + 1),
+                        ..Default::default()
+                    },
+                )]
+                .into(),
+                ..valid.clone()
+            },
+            MetadataError::NameTooLong,
+        ),
+    ];
+    for (metadata, expected) in cases {
+        assert_eq!(metadata.validate(), Err(expected), "{:?}", metadata);
+    }
+}
+
+#[test]
+fn test_metadata_decodes_newer_layouts() {
+    let metadata = SSRIMetadata {
+        name: String::from("Test UDT"),
+        symbol: String::from("TEST"),
+        decimals: 8,
+        ..Default::default()
+    };
+    let encoded = metadata.encode().unwrap();
+
+    // Rebuild the table with one more field, as a future version would.
+    let field_count = u32::from_le_bytes(encoded[4..8].try_into().unwrap()) as usize / 4 - 1;
+    let header_len = 4 + 4 * (field_count + 1);
+    let mut offsets = (0..field_count)
+        .map(|i| {
+            let at = 4 + 4 * i;
+            u32::from_le_bytes(encoded[at..at + 4].try_into().unwrap()) as usize + 4
+        })
+        .collect::<Vec<_>>();
+    let body = &encoded[4 + 4 * field_count..];
+    offsets.push(header_len + body.len());
+    let extra_field = 42u32.to_le_bytes();
+    let total_len = header_len + body.len() + extra_field.len();
+    let mut newer = (total_len as u32).to_le_bytes().to_vec();
+    for offset in offsets {
+        newer.extend_from_slice(&(offset as u32).to_le_bytes());
+    }
+    newer.extend_from_slice(body);
+    newer.extend_from_slice(&extra_field);
+
+    assert_eq!(SSRIMetadata::decode(&newer).unwrap(), metadata);
+}
+
+#[test]
+fn test_udt_args_forms() {
+    let type_id_args = [0x44; 32];
+    let legacy = UDTArgs::parse(&type_id_args).unwrap();
+    assert_eq!(legacy, UDTArgs::TypeId(type_id_args));
+    assert_eq!(
+        legacy.metadata_type_script(),
+        Some(type_id_script(&type_id_args))
+    );
+
+    // A selector byte names the singleton script guarding the metadata.
+    let cases = [
+        (MetadataSingleton::TypeId, 32, TYPE_ID_SCRIPT_CODE_HASH, 1u8),
+        (
+            MetadataSingleton::UniqueCell,
+            20,
+            UNIQUE_CELL_CODE_HASH,
+            UNIQUE_CELL_HASH_TYPE.into(),
+        ),
+    ];
+    for (singleton, args_len, code_hash, hash_type) in cases {
+        let raw = [vec![singleton as u8], vec![0x44; args_len]].concat();
+        let args = UDTArgs::parse(&raw).unwrap();
+        assert_eq!(args, UDTArgs::singleton(singleton, &raw[1..]));
+        assert_eq!(args.to_vec(), raw);
+        let script = args.metadata_type_script().unwrap();
+        assert_eq!(script.code_hash().as_slice(), code_hash);
+        assert_eq!(script.hash_type(), hash_type.into());
+        assert_eq!(script.args().raw_data(), raw[1..]);
+        assert!(!args.commits_to(&[]));
+
+        // The args must have the length the singleton script gives its cells.
+        assert_eq!(UDTArgs::parse(&[&raw[..], &[0]].concat()), None);
+    }
+    // Without the `testnet` feature the unique cell is mainnet's.
+    assert_eq!(UNIQUE_CELL_CODE_HASH, UNIQUE_CELL_MAINNET_CODE_HASH);
+    assert_eq!(UNIQUE_CELL_HASH_TYPE, ScriptHashType::Data1);
+    assert_ne!(UNIQUE_CELL_TESTNET_CODE_HASH, UNIQUE_CELL_MAINNET_CODE_HASH);
+
+    // Selector 1 is reserved.
+    assert_eq!(MetadataSingleton::try_from(1), Err(1));
+    assert_eq!(UDTArgs::parse(&[&[1u8][..], &[0x44; 32]].concat()), None);
+    assert_eq!(UDTArgs::parse(&[&[2u8][..], &[0x44; 32]].concat()), None);
+    assert_eq!(UDTArgs::parse(&[&[3u8][..], &[0x44; 32]].concat()), None);
+    assert_eq!(UDTArgs::parse(&[]), None);
+
+    let immutable = UDTArgs::immutable([0x36; 32], b"metadata");
+    assert_eq!(immutable.metadata_type_script(), None);
+}
@@ -1,4 +1,5 @@
 // NOTE: These tests currently depends on a running SSRI-server. See https://github.com/ckb-devrel/ssri-server
+// NOTE: To run them without a node, start `cargo run -p ssri-server-local -- crates/ssri-server-local/fixtures/ssri-udt.json` after `make build`.
 // NOTE: Due to the limitations of ckb_testtools at the moment (i.e. not able to obtain stable lock hashes), we will test paused sender/receiver transfer in ckb_ssri_cli
 
 use std::ffi::CString;
@@ -7,8 +8,9 @@ use ckb_std::high_level::{decode_hex, encode_hex};
 use reqwest::Client;
 use serde_json::json;
 
-use crate::utils::{get_ssri_response, method_path, method_path_hex};
+use crate::utils::{get_ssri_response, method_path_hex};
 use ckb_ssri_std::prelude::{decode_u64_vector, encode_u8_32_vector};
+use ssri_udt_sdk::argv::{self, method_path};
 
 // Get outpoint from deployment record
 const PASUABLE_UDT_TX_HASH: &str =
@@ -27,8 +29,9 @@ fn test_method_path() {
 async fn test_get_methods() {
     let url = "http://localhost:9090";
 
-    let get_methods_path_hex = method_path_hex("SSRI.get_methods");
-    println!("Get methods path: {}", get_methods_path_hex);
+    // Get all methods
+    let get_methods_argv = argv::to_hex(&argv::get_methods(0, 0));
+    println!("Get methods argv: {:?}", get_methods_argv);
     // Define the JSON payload
     let payload = json!({
         "id": 2,
@@ -37,7 +40,7 @@ async fn test_get_methods() {
         "params": [
             PASUABLE_UDT_TX_HASH,
             0,
-            [get_methods_path_hex, "0x0000000000000000", "0x0000000000000000"] // Get all methods
+            get_methods_argv
         ]
     });
 
@@ -78,8 +81,8 @@ async fn test_get_methods() {
 async fn test_version() {
     let url = "http://localhost:9090";
 
-    let version_path_hex = method_path_hex("SSRI.version");
-    println!("Version path: {:?}", version_path_hex);
+    let version_argv = argv::to_hex(&argv::version());
+    println!("Version argv: {:?}", version_argv);
 
     let payload = json!({
         "id": 2,
@@ -88,7 +91,7 @@ async fn test_version() {
         "params": [
             PASUABLE_UDT_TX_HASH,
             0,
-            [version_path_hex]
+            version_argv
         ]
     });
 
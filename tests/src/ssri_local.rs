@@ -0,0 +1,1756 @@
+// NOTE: These tests run SSRI methods in an in-process ckb-vm backed by the ckb-testtool context, so they need no ssri-server.
+
+use ckb_std::ckb_types::{bytes::Bytes, packed::*, prelude::*};
+use ckb_testtool::{
+    ckb_script::ScriptError,
+    ckb_types::{
+        core::{Capacity, TransactionBuilder, TransactionView},
+        prelude::IntoTransactionView,
+    },
+};
+use ssri_executor::{ExecutionError, Level, SSRIExecutor};
+use ssri_udt_sdk::{
+    argv, error_code,
+    metadata::CURRENT_METADATA_VERSION,
+    type_id::{calc_type_id, type_id_script},
+    CreatedScripts, IconDataRef, IconRef, LocalizedText, LockDelta, MetadataRecord,
+    MetadataSingleton, SSRIMetadata, SSRIMetadataV1, TransactionReport, UDTArgs, VerificationPath,
+    VerifyResult,
+};
+
+use crate::utils::{assert_script_error, build_test_context};
+
+#[test]
+fn test_local_metadata_methods() {
+    let test_context = build_test_context();
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    let script = &test_context.ssri_udt_type_script;
+
+    let name = executor
+        .run_script_level_script(&argv::name(), script)
+        .unwrap();
+    assert_eq!(name.content, Bytes::from("Test UDT"));
+    // Contract debug output is collected, not printed.
+    assert!(name
+        .debug
+        .iter()
+        .any(|line| line == "Entering ssri_methods"));
+    let symbol = executor
+        .run_script_level_script(&argv::symbol(), script)
+        .unwrap();
+    assert_eq!(symbol.content, Bytes::from("TEST"));
+    let decimals = executor
+        .run_script_level_script(&argv::decimals(), script)
+        .unwrap();
+    assert_eq!(decimals.content, Bytes::from(vec![8]));
+    let icon = executor
+        .run_script_level_script(&argv::icon(), script)
+        .unwrap();
+    assert_eq!(icon.content, Bytes::from("https://example.com/icon.png"));
+    println!("UDT.icon cycles: {}", icon.cycles);
+}
+
+#[test]
+fn test_local_extended_metadata_methods() {
+    let mut test_context = build_test_context();
+    let metadata = SSRIMetadata {
+        name: String::from("Extended UDT"),
+        symbol: String::from("EXT"),
+        decimals: 8,
+        description: String::from("A token for tests."),
+        website: String::from("https://example.com"),
+        social_links: vec![
+            String::from("https://x.com/example"),
+            String::from("https://t.me/example"),
+        ],
+        category: String::from("utility"),
+        extra: [(
+            String::from("audit"),
+            b"https://example.com/audit.pdf".to_vec(),
+        )]
+        .into(),
+        ..Default::default()
+    };
+    let type_id_args = [2u8; 32];
+    test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .type_(Some(type_id_script(&type_id_args)).pack())
+            .build(),
+        Bytes::from(metadata.encode().unwrap()),
+    );
+    let extended_udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(type_id_args.to_vec().pack())
+        .build();
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+
+    let description = executor
+        .run_script_level_script(&argv::description(), &extended_udt)
+        .unwrap();
+    assert_eq!(description.content, Bytes::from("A token for tests."));
+    let website = executor
+        .run_script_level_script(&argv::website(), &extended_udt)
+        .unwrap();
+    assert_eq!(website.content, Bytes::from("https://example.com"));
+    let social_links = executor
+        .run_script_level_script(&argv::social_links(), &extended_udt)
+        .unwrap();
+    let social_links = BytesVec::from_slice(&social_links.content)
+        .unwrap()
+        .into_iter()
+        .map(|link| String::from_utf8(link.raw_data().to_vec()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(social_links, metadata.social_links);
+    let category = executor
+        .run_script_level_script(&argv::category(), &extended_udt)
+        .unwrap();
+    assert_eq!(category.content, Bytes::from("utility"));
+    let audit = executor
+        .run_script_level_script(&argv::extra("audit"), &extended_udt)
+        .unwrap();
+    assert_eq!(audit.content, Bytes::from("https://example.com/audit.pdf"));
+    let missing = executor
+        .run_script_level_script(&argv::extra("missing"), &extended_udt)
+        .unwrap_err();
+    assert!(matches!(
+        missing,
+        ExecutionError::ExitCode(error_code::METADATA_EXTRA_KEY_NOT_FOUND)
+    ));
+
+    // Metadata without the explorer fields reads back empty.
+    let fixture = &test_context.ssri_udt_type_script;
+    let description = executor
+        .run_script_level_script(&argv::description(), fixture)
+        .unwrap();
+    assert!(description.content.is_empty());
+    let social_links = executor
+        .run_script_level_script(&argv::social_links(), fixture)
+        .unwrap();
+    assert!(BytesVec::from_slice(&social_links.content)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_local_metadata_record() {
+    let mut test_context = build_test_context();
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    let fixture = &test_context.ssri_udt_type_script;
+
+    let record = executor
+        .run_script_level_script(&argv::metadata(), fixture)
+        .unwrap();
+    let record = MetadataRecord::decode(&record.content).unwrap();
+    let (_, fixture_data) = test_context
+        .context
+        .get_cell(&test_context.ssri_metadata_out_point)
+        .unwrap();
+    assert_eq!(
+        record.metadata,
+        SSRIMetadata::decode(&fixture_data).unwrap()
+    );
+    assert_eq!(record.out_point(), test_context.ssri_metadata_out_point);
+    assert_eq!(record.owner_lock(), test_context.admin_lock_script);
+
+    // A v1 cell comes back in the current layout, keeping its version.
+    let v1 = SSRIMetadataV1 {
+        name: String::from("Legacy UDT"),
+        symbol: String::from("OLD"),
+        decimals: 6,
+        icon: String::new(),
+    };
+    let type_id_args = [0x0au8; 32];
+    let v1_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(test_context.normal_user_a_lock_script.clone())
+            .type_(Some(type_id_script(&type_id_args)).pack())
+            .build(),
+        Bytes::from(serde_molecule::to_vec(&v1, false).unwrap()),
+    );
+    let v1_udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(type_id_args.to_vec().pack())
+        .build();
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    let record = executor
+        .run_script_level_script(&argv::metadata(), &v1_udt)
+        .unwrap();
+    let record = MetadataRecord::decode(&record.content).unwrap();
+    assert_eq!(record.metadata, SSRIMetadata::from(v1));
+    assert_eq!(record.metadata.version, 1);
+    assert_eq!(record.out_point(), v1_out_point);
+    assert_eq!(record.owner_lock(), test_context.normal_user_a_lock_script);
+
+    // Immutable args have no metadata cell to report.
+    let immutable_udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(
+            UDTArgs::immutable([0x36; 32], &fixture_data)
+                .to_vec()
+                .pack(),
+        )
+        .build();
+    let err = executor
+        .run_script_level_script(&argv::metadata(), &immutable_udt)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ExecutionError::ExitCode(error_code::IMMUTABLE_METADATA)
+    ));
+}
+
+#[test]
+fn test_local_singleton_metadata() {
+    let mut test_context = build_test_context();
+    let metadata = SSRIMetadata {
+        name: String::from("Guarded UDT"),
+        symbol: String::from("GRD"),
+        decimals: 8,
+        ..Default::default()
+    };
+    let guarded = [
+        (MetadataSingleton::UniqueCell, vec![0x0b; 20]),
+        (MetadataSingleton::TypeId, vec![0x0b; 32]),
+    ];
+    let mut cells = Vec::new();
+    for (singleton, args) in &guarded {
+        let out_point = test_context.context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(test_context.admin_lock_script.clone())
+                .type_(Some(singleton.script(args)).pack())
+                .build(),
+            Bytes::from(metadata.encode().unwrap()),
+        );
+        cells.push(out_point);
+    }
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+
+    for ((singleton, args), out_point) in guarded.iter().zip(cells) {
+        let udt = test_context
+            .ssri_udt_type_script
+            .clone()
+            .as_builder()
+            .args(UDTArgs::singleton(*singleton, args).to_vec().pack())
+            .build();
+        let name = executor
+            .run_script_level_script(&argv::name(), &udt)
+            .unwrap();
+        assert_eq!(name.content, Bytes::from("Guarded UDT"), "{:?}", singleton);
+        let record = executor
+            .run_script_level_script(&argv::metadata(), &udt)
+            .unwrap();
+        let record = MetadataRecord::decode(&record.content).unwrap();
+        assert_eq!(record.out_point(), out_point, "{:?}", singleton);
+    }
+
+    // Args naming no live cell find no metadata.
+    let unguarded = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(
+            UDTArgs::singleton(MetadataSingleton::UniqueCell, &[0x0c; 20])
+                .to_vec()
+                .pack(),
+        )
+        .build();
+    let err = executor
+        .run_script_level_script(&argv::name(), &unguarded)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ExecutionError::ExitCode(error_code::SSRI_CONFIG_NOT_FOUND)
+    ));
+}
+
+#[test]
+fn test_local_localized_metadata() {
+    let mut test_context = build_test_context();
+    let localized = |name: &str, description: &str| LocalizedText {
+        name: name.to_string(),
+        description: description.to_string(),
+    };
+    let metadata = SSRIMetadata {
+        name: String::from("Test UDT"),
+        symbol: String::from("TEST"),
+        description: String::from("A token for tests."),
+        localized: [
+            (String::from("zh"), localized("测试币", "测试用代币。")),
+            (String::from("zh-TW"), localized("測試幣", "")),
+        ]
+        .into(),
+        ..Default::default()
+    };
+    let type_id_args = [8u8; 32];
+    test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .type_(Some(type_id_script(&type_id_args)).pack())
+            .build(),
+        Bytes::from(metadata.encode().unwrap()),
+    );
+    let localized_udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(type_id_args.to_vec().pack())
+        .build();
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+
+    let cases = [
+        // Exact match.
+        ("zh-TW", "測試幣", "A token for tests."),
+        // Primary language subtag.
+        ("zh-CN", "测试币", "测试用代币。"),
+        ("zh", "测试币", "测试用代币。"),
+        // Default.
+        ("fr", "Test UDT", "A token for tests."),
+        ("", "Test UDT", "A token for tests."),
+    ];
+    for (locale, name, description) in cases {
+        let execution = executor
+            .run_script_level_script(&argv::name_localized(locale), &localized_udt)
+            .unwrap();
+        assert_eq!(
+            execution.content,
+            Bytes::from(name.to_string()),
+            "{}",
+            locale
+        );
+        let execution = executor
+            .run_script_level_script(&argv::description_localized(locale), &localized_udt)
+            .unwrap();
+        assert_eq!(
+            execution.content,
+            Bytes::from(description.to_string()),
+            "{}",
+            locale
+        );
+    }
+}
+
+#[test]
+fn test_local_large_metadata() {
+    let mut test_context = build_test_context();
+    // Well past the 256-byte buffer of the first load attempt.
+    let icon = format!("data:image/svg+xml;base64,{}", "A".repeat(4096));
+    let metadata = SSRIMetadata {
+        name: String::from("Large UDT"),
+        symbol: String::from("LRG"),
+        icon: icon.clone(),
+        ..Default::default()
+    };
+    let type_id_args = [10u8; 32];
+    test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .type_(Some(type_id_script(&type_id_args)).pack())
+            .build(),
+        Bytes::from(metadata.encode().unwrap()),
+    );
+    let large_udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(type_id_args.to_vec().pack())
+        .build();
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    let execution = executor
+        .run_script_level_script(&argv::icon(), &large_udt)
+        .unwrap();
+    assert_eq!(execution.content, Bytes::from(icon));
+}
+
+#[test]
+fn test_local_icon_ref() {
+    let mut test_context = build_test_context();
+    let icon = Bytes::from_static(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>");
+    let icon_type_id_args = [3u8; 32];
+    test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .type_(Some(type_id_script(&icon_type_id_args)).pack())
+            .build(),
+        icon.clone(),
+    );
+    let fixed_icon_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        icon.clone(),
+    );
+    let data_ref = |data_hash: [u8; 32]| {
+        IconRef::DataHash(IconDataRef {
+            tx_hash: fixed_icon_out_point.tx_hash().unpack(),
+            index: fixed_icon_out_point.index().unpack(),
+            data_hash,
+        })
+    };
+
+    let cases = [
+        (4u8, IconRef::TypeId(icon_type_id_args), None),
+        (
+            5,
+            data_ref(CellOutput::calc_data_hash(&icon).unpack()),
+            None,
+        ),
+        (
+            6,
+            IconRef::TypeId([9u8; 32]),
+            Some(error_code::METADATA_ICON_NOT_FOUND),
+        ),
+        (
+            7,
+            data_ref([0u8; 32]),
+            Some(error_code::METADATA_ICON_HASH_MISMATCH),
+        ),
+    ];
+    let mut udts = Vec::new();
+    for (type_id_byte, icon_ref, expected_error) in cases {
+        let metadata = SSRIMetadata {
+            name: String::from("Icon UDT"),
+            symbol: String::from("ICON"),
+            icon_ref: Some(icon_ref),
+            ..Default::default()
+        };
+        let type_id_args = [type_id_byte; 32];
+        test_context.context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(test_context.admin_lock_script.clone())
+                .type_(Some(type_id_script(&type_id_args)).pack())
+                .build(),
+            Bytes::from(metadata.encode().unwrap()),
+        );
+        let udt = test_context
+            .ssri_udt_type_script
+            .clone()
+            .as_builder()
+            .args(type_id_args.to_vec().pack())
+            .build();
+        udts.push((udt, expected_error));
+    }
+
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    for (udt, expected_error) in udts {
+        let result = executor.run_script_level_script(&argv::icon(), &udt);
+        match expected_error {
+            None => assert_eq!(result.unwrap().content, icon),
+            Some(code) => assert!(
+                matches!(result, Err(ExecutionError::ExitCode(exit_code)) if exit_code == code),
+                "{:?}",
+                result
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_local_ssri_methods() {
+    let test_context = build_test_context();
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+
+    let version = executor.run_script_level_code(&argv::version()).unwrap();
+    assert_eq!(version.content, Bytes::from(vec![0]));
+
+    let has_methods = executor
+        .run_script_level_code(&argv::has_methods(&[
+            argv::UDT_MINT,
+            argv::SSRIUDT_CREATE,
+            "UDT.pause",
+        ]))
+        .unwrap();
+    assert_eq!(&has_methods.content[4..], &[1, 1, 0]);
+
+    // SSRI.version, SSRI.get_methods and SSRI.has_methods are always listed first.
+    let get_methods = executor
+        .run_script_level_code(&argv::get_methods(3, 0))
+        .unwrap();
+    let paths = get_methods.content[4..]
+        .chunks(8)
+        .map(|path| u64::from_le_bytes(path.try_into().unwrap()))
+        .collect::<Vec<_>>();
+    assert!(paths.contains(&argv::method_path(argv::UDT_TRANSFER)));
+
+    let not_found = executor
+        .run_script_level_code(&[argv::method_path("UDT.pause").to_le_bytes().to_vec()])
+        .unwrap_err();
+    assert!(matches!(
+        not_found,
+        ExecutionError::ExitCode(error_code::SSRI_METHODS_NOT_FOUND)
+    ));
+}
+
+#[test]
+fn test_local_mint() {
+    let mut test_context = build_test_context();
+    let mint_amount = 20000000000u128;
+
+    let mint_tx = {
+        let executor =
+            SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+        let argv = argv::mint(
+            None,
+            &[test_context.normal_user_b_lock_script.clone()],
+            &[mint_amount],
+        )
+        .unwrap();
+        let execution = executor
+            .run_script_level_script(&argv, &test_context.ssri_udt_type_script)
+            .unwrap();
+        Transaction::from_slice(&execution.content).unwrap()
+    };
+
+    let raw = mint_tx.raw();
+    assert_eq!(raw.outputs().len(), 1);
+    assert_eq!(
+        raw.outputs().get(0).unwrap().type_().to_opt(),
+        Some(test_context.ssri_udt_type_script.clone())
+    );
+    assert_eq!(
+        raw.outputs_data().get(0).unwrap().raw_data(),
+        Bytes::from(mint_amount.to_le_bytes().to_vec())
+    );
+    assert_eq!(
+        raw.cell_deps().get(0).unwrap().out_point(),
+        test_context.ssri_metadata_out_point
+    );
+
+    let admin_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let mint_tx = mint_tx
+        .into_view()
+        .as_advanced_builder()
+        .input(
+            CellInput::new_builder()
+                .previous_output(admin_out_point)
+                .build(),
+        )
+        .cell_dep(test_context.ssri_udt_dep.clone())
+        .cell_dep(test_context.always_success_dep.clone())
+        .build();
+
+    let cycles = test_context
+        .context
+        .verify_tx(&mint_tx, u64::MAX)
+        .expect("Local Mint Tx Failed");
+    println!("Local Mint Tx cycles: {}", cycles);
+}
+
+#[test]
+fn test_local_transfer() {
+    let mut test_context = build_test_context();
+    let wallet_amount = 20000000000u128;
+    let transfer_amount = 10000000000u128;
+
+    let wallet_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(100u64.pack())
+            .lock(test_context.normal_user_a_lock_script.clone())
+            .type_(Some(test_context.ssri_udt_type_script.clone()).pack())
+            .build(),
+        Bytes::from(wallet_amount.to_le_bytes().to_vec()),
+    );
+    let held_tx = TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(wallet_out_point)
+                .build(),
+        )
+        .cell_dep(test_context.ssri_udt_dep.clone())
+        .cell_dep(test_context.always_success_dep.clone())
+        .build();
+
+    let transfer_tx = {
+        let executor =
+            SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+        let argv = argv::transfer(
+            Some(&held_tx.data()),
+            &[
+                test_context.normal_user_b_lock_script.clone(),
+                test_context.normal_user_a_lock_script.clone(),
+            ],
+            &[transfer_amount, wallet_amount - transfer_amount],
+        )
+        .unwrap();
+        let execution = executor
+            .run_script_level_script(&argv, &test_context.ssri_udt_type_script)
+            .unwrap();
+        Transaction::from_slice(&execution.content).unwrap()
+    };
+    assert_eq!(
+        transfer_tx.raw().inputs().as_slice(),
+        held_tx.data().raw().inputs().as_slice()
+    );
+    assert_eq!(transfer_tx.raw().outputs().len(), 2);
+
+    let transfer_tx = transfer_tx
+        .into_view()
+        .as_advanced_builder()
+        .set_cell_deps(vec![
+            test_context.ssri_udt_dep.clone(),
+            test_context.always_success_dep.clone(),
+        ])
+        .build();
+    let cycles = test_context
+        .context
+        .verify_tx(&transfer_tx, u64::MAX)
+        .expect("Local Transfer Tx Failed");
+    println!("Local Transfer Tx cycles: {}", cycles);
+}
+
+#[test]
+fn test_local_builders_keep_tx_fields() {
+    let test_context = build_test_context();
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    let held_tx = TransactionBuilder::default()
+        .version(1u32.pack())
+        .cell_dep(test_context.ssri_udt_dep.clone())
+        .cell_dep(test_context.always_success_dep.clone())
+        .header_dep([0x11; 32].pack())
+        .input(CellInput::new(
+            test_context.ssri_metadata_out_point.clone(),
+            7,
+        ))
+        .output(
+            CellOutput::new_builder()
+                .capacity(500u64.pack())
+                .lock(test_context.admin_lock_script.clone())
+                .build(),
+        )
+        .output_data(Bytes::from("kept").pack())
+        .witness(Bytes::from("signature").pack())
+        .build()
+        .data();
+    let to_locks = [
+        test_context.normal_user_a_lock_script.clone(),
+        test_context.normal_user_b_lock_script.clone(),
+    ];
+    let to_amounts = [100u128, 200];
+
+    let run = |argv: argv::Argv| {
+        let execution = executor
+            .run_script_level_script(&argv, &test_context.ssri_udt_type_script)
+            .unwrap();
+        Transaction::from_slice(&execution.content).unwrap()
+    };
+    let transfer_tx = run(argv::transfer(Some(&held_tx), &to_locks, &to_amounts).unwrap());
+    let mint_tx = run(argv::mint(Some(&held_tx), &to_locks, &to_amounts).unwrap());
+
+    for (tx, extra_cell_deps) in [
+        (transfer_tx, vec![]),
+        (mint_tx, vec![test_context.ssri_metadata_dep.clone()]),
+    ] {
+        let (raw, held) = (tx.raw(), held_tx.raw());
+        assert_eq!(raw.version(), held.version());
+        assert_eq!(raw.header_deps().as_slice(), held.header_deps().as_slice());
+        assert_eq!(raw.inputs().as_slice(), held.inputs().as_slice());
+        assert_eq!(tx.witnesses().as_slice(), held_tx.witnesses().as_slice());
+        let cell_deps = held
+            .cell_deps()
+            .into_iter()
+            .chain(extra_cell_deps)
+            .collect::<Vec<_>>();
+        assert_eq!(raw.cell_deps().into_iter().collect::<Vec<_>>(), cell_deps);
+
+        // The held output stays first, followed by one UDT output per recipient.
+        assert_eq!(raw.outputs().len(), 3);
+        assert_eq!(raw.outputs().get(0), held.outputs().get(0));
+        assert_eq!(raw.outputs_data().get(0), held.outputs_data().get(0));
+        for (i, (lock, amount)) in to_locks.iter().zip(to_amounts).enumerate() {
+            let output = raw.outputs().get(i + 1).unwrap();
+            assert_eq!(&output.lock(), lock);
+            assert_eq!(
+                output.type_().to_opt(),
+                Some(test_context.ssri_udt_type_script.clone())
+            );
+            assert_eq!(
+                raw.outputs_data().get(i + 1).unwrap().raw_data(),
+                Bytes::from(amount.to_le_bytes().to_vec())
+            );
+        }
+    }
+
+    // Recipients and amounts must pair up.
+    let mismatched = argv::mint(Some(&held_tx), &to_locks, &[100]).unwrap();
+    let err = executor
+        .run_script_level_script(&mismatched, &test_context.ssri_udt_type_script)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ExecutionError::ExitCode(error_code::SSRI_METHODS_ARGS_INVALID)
+    ));
+}
+
+#[test]
+fn test_local_create() {
+    let mut test_context = build_test_context();
+
+    let input_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let input = CellInput::new_builder()
+        .previous_output(input_out_point)
+        .build();
+    let held_tx = TransactionBuilder::default()
+        .input(input.clone())
+        .cell_dep(test_context.always_success_dep.clone())
+        .build();
+    let metadata = SSRIMetadata {
+        name: String::from("Created UDT"),
+        symbol: String::from("CRT"),
+        decimals: 6,
+        icon: String::from("https://example.com/created.png"),
+        ..Default::default()
+    };
+
+    let create_tx = {
+        let executor =
+            SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+        let argv =
+            argv::create(&held_tx.data(), &test_context.admin_lock_script, &metadata).unwrap();
+        let execution = executor.run(&argv, Level::Code).unwrap();
+        Transaction::from_slice(&execution.content).unwrap()
+    };
+
+    let metadata_output = create_tx.raw().outputs().get(0).unwrap();
+    assert_eq!(
+        metadata_output.type_().to_opt(),
+        Some(type_id_script(&calc_type_id(&input, 0)))
+    );
+
+    let cycles = test_context
+        .context
+        .verify_tx(&create_tx.into_view(), u64::MAX)
+        .expect("Local Create Tx Failed");
+    println!("Local Create Tx cycles: {}", cycles);
+}
+
+#[test]
+fn test_local_describe_transaction() {
+    let mut test_context = build_test_context();
+    let udt = test_context.ssri_udt_type_script.clone();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let udt_output = |lock: &Script| {
+        CellOutput::new_builder()
+            .capacity(100u64.pack())
+            .lock(lock.clone())
+            .type_(Some(udt.clone()).pack())
+            .build()
+    };
+    let amount_data = |amount: u128| Bytes::from(amount.to_le_bytes().to_vec());
+
+    let wallet_out_point = test_context
+        .context
+        .create_cell(udt_output(&user_a), amount_data(1000));
+    let admin_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let invalid_metadata_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .type_(UDTArgs::TypeId([0; 32]).metadata_type_script().pack())
+            .build(),
+        Bytes::from_static(b"not metadata"),
+    );
+    let input = |out_point: &OutPoint| {
+        CellInput::new_builder()
+            .previous_output(out_point.clone())
+            .build()
+    };
+    let describe = |tx: TransactionView| {
+        let executor =
+            SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+        let execution = executor
+            .run(
+                &argv::describe_transaction(),
+                Level::Transaction {
+                    script: udt.clone(),
+                    tx,
+                },
+            )
+            .unwrap();
+        TransactionReport::decode(&execution.content).unwrap()
+    };
+
+    let transfer_tx = TransactionBuilder::default()
+        .input(input(&wallet_out_point))
+        .output(udt_output(&user_b))
+        .output_data(amount_data(600).pack())
+        .output(udt_output(&user_a))
+        .output_data(amount_data(400).pack())
+        .build();
+    let report = describe(transfer_tx);
+    assert_eq!(report.verification_path(), Some(VerificationPath::Transfer));
+    assert_eq!(report.transferred, 1000);
+    assert_eq!((report.minted, report.burned), (0, 0));
+    assert!(!report.owner_mode);
+    let mut expected = vec![
+        LockDelta {
+            lock_hash: user_a.calc_script_hash().unpack(),
+            input_amount: 1000,
+            output_amount: 400,
+        },
+        LockDelta {
+            lock_hash: user_b.calc_script_hash().unpack(),
+            input_amount: 0,
+            output_amount: 600,
+        },
+    ];
+    expected.sort_by_key(|delta| delta.lock_hash);
+    assert_eq!(report.lock_deltas, expected);
+
+    let mint_tx = TransactionBuilder::default()
+        .input(input(&admin_out_point))
+        .cell_dep(
+            CellDep::new_builder()
+                .out_point(test_context.ssri_metadata_out_point.clone())
+                .build(),
+        )
+        .output(udt_output(&user_b))
+        .output_data(amount_data(500).pack())
+        .build();
+    let report = describe(mint_tx);
+    assert_eq!(report.verification_path(), Some(VerificationPath::Mint));
+    assert_eq!(report.minted, 500);
+    assert!(report.owner_mode);
+
+    // Owner mode does not depend on the metadata being valid.
+    let invalid_mint_tx = TransactionBuilder::default()
+        .input(input(&admin_out_point))
+        .cell_dep(
+            CellDep::new_builder()
+                .out_point(invalid_metadata_out_point)
+                .build(),
+        )
+        .output(udt_output(&user_b))
+        .output_data(amount_data(500).pack())
+        .build();
+    assert!(describe(invalid_mint_tx).owner_mode);
+
+    let burn_tx = TransactionBuilder::default()
+        .input(input(&wallet_out_point))
+        .output(udt_output(&user_a))
+        .output_data(amount_data(300).pack())
+        .build();
+    let report = describe(burn_tx);
+    assert_eq!(report.verification_path(), Some(VerificationPath::Rejected));
+    assert_eq!((report.burned, report.transferred), (700, 300));
+
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    let script_level = executor
+        .run_script_level_script(&argv::describe_transaction(), &udt)
+        .unwrap_err();
+    assert!(matches!(
+        script_level,
+        ExecutionError::ExitCode(error_code::SSRI_METHOD_REQUIRE_HIGHER_LEVEL)
+    ));
+}
+
+#[test]
+fn test_local_verify() {
+    let mut test_context = build_test_context();
+    let udt = test_context.ssri_udt_type_script.clone();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let udt_output = |lock: &Script| {
+        CellOutput::new_builder()
+            .capacity(100u64.pack())
+            .lock(lock.clone())
+            .type_(Some(udt.clone()).pack())
+            .build()
+    };
+    let amount_data = |amount: u128| Bytes::from(amount.to_le_bytes().to_vec());
+
+    let wallet_out_point = test_context
+        .context
+        .create_cell(udt_output(&user_a), amount_data(1000));
+    let admin_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let input = |out_point: &OutPoint| {
+        CellInput::new_builder()
+            .previous_output(out_point.clone())
+            .build()
+    };
+    let metadata_dep = CellDep::new_builder()
+        .out_point(test_context.ssri_metadata_out_point.clone())
+        .build();
+    let verify = |tx: TransactionView| {
+        let executor =
+            SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+        let execution = executor
+            .run(
+                &argv::verify(),
+                Level::Transaction {
+                    script: udt.clone(),
+                    tx,
+                },
+            )
+            .unwrap();
+        VerifyResult::decode(&execution.content).unwrap()
+    };
+    let failure = |result: VerifyResult| (result.passed, result.error_code, result.error);
+
+    let transfer_tx = TransactionBuilder::default()
+        .input(input(&wallet_out_point))
+        .output(udt_output(&user_a))
+        .output_data(amount_data(1000).pack())
+        .build();
+    let result = verify(transfer_tx);
+    assert!(result.passed);
+    assert_eq!((result.error_code, result.error.as_str()), (0, ""));
+
+    let burn_tx = TransactionBuilder::default()
+        .input(input(&wallet_out_point))
+        .output(udt_output(&user_a))
+        .output_data(amount_data(300).pack())
+        .build();
+    assert_eq!(
+        failure(verify(burn_tx)),
+        (
+            false,
+            error_code::INSUFFICIENT_BALANCE as u8,
+            String::from("InsufficientBalance")
+        )
+    );
+
+    let unauthorized_mint_tx = TransactionBuilder::default()
+        .input(input(&wallet_out_point))
+        .cell_dep(metadata_dep.clone())
+        .output(udt_output(&user_a))
+        .output_data(amount_data(2000).pack())
+        .build();
+    assert_eq!(
+        failure(verify(unauthorized_mint_tx)),
+        (
+            false,
+            error_code::NO_MINT_PERMISSION as u8,
+            String::from("NoMintPermission")
+        )
+    );
+
+    let mint_without_metadata_tx = TransactionBuilder::default()
+        .input(input(&admin_out_point))
+        .output(udt_output(&user_a))
+        .output_data(amount_data(500).pack())
+        .build();
+    assert_eq!(
+        failure(verify(mint_without_metadata_tx)),
+        (
+            false,
+            error_code::SSRI_CONFIG_NOT_FOUND as u8,
+            String::from("SSRIConfigNotFound")
+        )
+    );
+
+    let mint_tx = TransactionBuilder::default()
+        .input(input(&admin_out_point))
+        .cell_dep(metadata_dep)
+        .output(udt_output(&user_a))
+        .output_data(amount_data(500).pack())
+        .build();
+    assert!(verify(mint_tx).passed);
+
+    let malformed_tx = TransactionBuilder::default()
+        .input(input(&wallet_out_point))
+        .output(udt_output(&user_a))
+        .output_data(Bytes::from(vec![0; 8]).pack())
+        .build();
+    assert_eq!(
+        failure(verify(malformed_tx)),
+        (false, error_code::ENCODING as u8, String::from("Encoding"))
+    );
+}
+
+#[test]
+fn test_local_airdrop() {
+    let mut test_context = build_test_context();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+    let recipients = (0u8..4)
+        .map(|i| {
+            user_a
+                .clone()
+                .as_builder()
+                .args(Bytes::from(vec![i]).pack())
+                .build()
+        })
+        .collect::<Vec<_>>();
+    let to_lock_vec = [
+        recipients.clone(),
+        vec![recipients[1].clone(), user_b.clone()],
+    ]
+    .concat();
+    let to_amount_vec = [100, 200, 300, 400, 50, 600];
+
+    let held_tx = TransactionBuilder::default()
+        .output(
+            CellOutput::new_builder()
+                .lock(test_context.admin_lock_script.clone())
+                .build(),
+        )
+        .output_data(Bytes::new().pack())
+        .build();
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    let run = |max_outputs: u32| {
+        let argv = argv::airdrop(
+            Some(&held_tx.data()),
+            &to_lock_vec,
+            &to_amount_vec,
+            max_outputs,
+        )
+        .unwrap();
+        executor.run_script_level_script(&argv, &test_context.ssri_udt_type_script)
+    };
+
+    let invalid = run(0).unwrap_err();
+    assert!(matches!(
+        invalid,
+        ExecutionError::ExitCode(error_code::SSRI_METHODS_ARGS_INVALID)
+    ));
+
+    let execution = run(2).unwrap();
+    let txs = TransactionVec::from_slice(&execution.content).unwrap();
+    assert_eq!(txs.len(), 3);
+    let airdropped = txs
+        .clone()
+        .into_iter()
+        .flat_map(|tx| {
+            let raw = tx.raw();
+            raw.outputs()
+                .into_iter()
+                .zip(raw.outputs_data())
+                .filter(|(output, _)| output.type_().is_some())
+                .map(|(output, data)| {
+                    let amount = u128::from_le_bytes(data.raw_data().as_ref().try_into().unwrap());
+                    (output.lock(), amount)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        airdropped,
+        vec![
+            (recipients[0].clone(), 100),
+            (recipients[1].clone(), 250),
+            (recipients[2].clone(), 300),
+            (recipients[3].clone(), 400),
+            (user_b.clone(), 600),
+        ]
+    );
+    // The held transaction only seeds the first batch. Every batch but the
+    // last ends with an owner output the next one spends.
+    let outputs_len = txs
+        .clone()
+        .into_iter()
+        .map(|tx| tx.raw().outputs().len())
+        .collect::<Vec<_>>();
+    assert_eq!(outputs_len, vec![4, 3, 1]);
+    for (previous, tx) in txs.clone().into_iter().zip(txs.clone().into_iter().skip(1)) {
+        let chain_index = previous.raw().outputs().len() - 1;
+        let chain_output = previous.raw().outputs().get(chain_index).unwrap();
+        assert_eq!(chain_output.lock(), test_context.admin_lock_script);
+        assert!(chain_output.type_().is_none());
+        // Linking to the chain output is left to the caller.
+        assert!(tx.raw().inputs().is_empty());
+    }
+    for tx in txs.clone().into_iter() {
+        assert_eq!(tx.raw().cell_deps().len(), 1);
+    }
+    drop(executor);
+
+    // The next batch spends the chain output of the completed batch before
+    // it.
+    let mut previous: Option<TransactionView> = None;
+    for tx in txs.into_iter() {
+        let chain_input = match &previous {
+            None => test_context.context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(10000u64.pack())
+                    .lock(test_context.admin_lock_script.clone())
+                    .build(),
+                Bytes::default(),
+            ),
+            Some(previous) => {
+                let chain_index = previous.outputs().len() - 1;
+                let out_point = OutPoint::new(previous.hash(), chain_index as u32);
+                test_context.context.create_cell_with_out_point(
+                    out_point.clone(),
+                    previous.output(chain_index).unwrap(),
+                    previous.outputs_data().get(chain_index).unwrap().raw_data(),
+                );
+                out_point
+            }
+        };
+        let tx = tx
+            .into_view()
+            .as_advanced_builder()
+            .set_inputs(vec![CellInput::new_builder()
+                .previous_output(chain_input)
+                .build()])
+            .cell_dep(test_context.ssri_udt_dep.clone())
+            .cell_dep(test_context.always_success_dep.clone())
+            .build();
+        let cycles = test_context
+            .context
+            .verify_tx(&tx, u64::MAX)
+            .expect("Local Airdrop Tx Failed");
+        println!("Local Airdrop Tx cycles: {}", cycles);
+        previous = Some(tx);
+    }
+}
+
+#[test]
+fn test_local_create_rejects_invalid_metadata() {
+    let mut test_context = build_test_context();
+
+    let input_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let held_tx = TransactionBuilder::default()
+        .input(
+            CellInput::new_builder()
+                .previous_output(input_out_point)
+                .build(),
+        )
+        .build();
+    let valid = SSRIMetadata {
+        name: String::from("Created UDT"),
+        symbol: String::from("CRT"),
+        decimals: 6,
+        icon: String::from("https://example.com/created.png"),
+        ..Default::default()
+    };
+
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    // Each rule has its own exit code.
+    let cases = [
+        (
+            SSRIMetadata {
+                decimals: 39,
+                ..valid.clone()
+            },
+            error_code::INVALID_METADATA_DECIMALS,
+        ),
+        (
+            SSRIMetadata {
+                name: String::new(),
+                ..valid.clone()
+            },
+            error_code::EMPTY_METADATA_NAME,
+        ),
+        (
+            SSRIMetadata {
+                symbol: String::from("C R T"),
+                ..valid.clone()
+            },
+            error_code::INVALID_METADATA_SYMBOL_CHARACTER,
+        ),
+        (
+            SSRIMetadata {
+                icon: String::from("ipfs://created.png"),
+                ..valid.clone()
+            },
+            error_code::INVALID_METADATA_ICON_SCHEME,
+        ),
+    ];
+    for (metadata, exit_code) in cases {
+        let argv =
+            argv::create(&held_tx.data(), &test_context.admin_lock_script, &metadata).unwrap();
+        let err = executor.run(&argv, Level::Code).unwrap_err();
+        assert!(
+            matches!(err, ExecutionError::ExitCode(code) if code == exit_code),
+            "{:?}: {}",
+            metadata,
+            err
+        );
+    }
+}
+
+#[test]
+fn test_local_created_scripts() {
+    let mut test_context = build_test_context();
+
+    let input_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let input = CellInput::new_builder()
+        .previous_output(input_out_point)
+        .build();
+    // A plain output first, so the metadata cell is not at index 0.
+    let held_tx = TransactionBuilder::default()
+        .input(input.clone())
+        .output(
+            CellOutput::new_builder()
+                .lock(test_context.admin_lock_script.clone())
+                .build(),
+        )
+        .output_data(Bytes::new().pack())
+        .build();
+    let metadata = SSRIMetadata {
+        name: String::from("Created UDT"),
+        symbol: String::from("CRT"),
+        decimals: 6,
+        icon: String::new(),
+        ..Default::default()
+    };
+
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    let argv = argv::create(&held_tx.data(), &test_context.admin_lock_script, &metadata).unwrap();
+    let create_tx =
+        Transaction::from_slice(&executor.run(&argv, Level::Code).unwrap().content).unwrap();
+
+    // Any ssri-udt type script will do; only its code hash and hash type matter.
+    let any_udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(Bytes::new().pack())
+        .build();
+    let execution = executor
+        .run_script_level_script(&argv::created_scripts(&create_tx), &any_udt)
+        .unwrap();
+    let (metadata_type_script, udt_type_script) = CreatedScripts::decode(&execution.content)
+        .unwrap()
+        .into_scripts();
+    let type_id_args = calc_type_id(&input, 1);
+    assert_eq!(metadata_type_script, type_id_script(&type_id_args));
+    assert_eq!(
+        udt_type_script,
+        any_udt
+            .clone()
+            .as_builder()
+            .args(type_id_args.to_vec().pack())
+            .build()
+    );
+
+    let not_created = executor
+        .run_script_level_script(&argv::created_scripts(&held_tx.data()), &any_udt)
+        .unwrap_err();
+    assert!(matches!(
+        not_created,
+        ExecutionError::ExitCode(error_code::SSRI_CONFIG_NOT_FOUND)
+    ));
+}
+
+#[test]
+fn test_local_create_and_mint() {
+    let mut test_context = build_test_context();
+    let admin_lock = test_context.admin_lock_script.clone();
+    let user_b = test_context.normal_user_b_lock_script.clone();
+
+    let input_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(admin_lock.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let input = CellInput::new_builder()
+        .previous_output(input_out_point)
+        .build();
+    let held_tx = TransactionBuilder::default().input(input.clone()).build();
+    let metadata = SSRIMetadata {
+        name: String::from("Genesis UDT"),
+        symbol: String::from("GEN"),
+        decimals: 8,
+        icon: String::new(),
+        ..Default::default()
+    };
+    let any_udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(Bytes::new().pack())
+        .build();
+
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    let create_and_mint = |owner_lock: &Script| {
+        let argv = argv::create_and_mint(
+            &held_tx.data(),
+            owner_lock,
+            &metadata,
+            &[admin_lock.clone(), user_b.clone()],
+            &[1_000_000, 500],
+        )
+        .unwrap();
+        let execution = executor.run_script_level_script(&argv, &any_udt).unwrap();
+        Transaction::from_slice(&execution.content)
+            .unwrap()
+            .into_view()
+            .as_advanced_builder()
+            .cell_dep(test_context.ssri_udt_dep.clone())
+            .cell_dep(test_context.always_success_dep.clone())
+            .build()
+    };
+
+    let genesis_tx = create_and_mint(&admin_lock);
+    let type_id_args = calc_type_id(&input, 0);
+    let udt_type_script = any_udt
+        .clone()
+        .as_builder()
+        .args(type_id_args.to_vec().pack())
+        .build();
+    assert_eq!(
+        genesis_tx.outputs().get(0).unwrap().type_().to_opt(),
+        Some(type_id_script(&type_id_args))
+    );
+    assert_eq!(genesis_tx.outputs().len(), 3);
+    for output in genesis_tx.outputs().into_iter().skip(1) {
+        assert_eq!(output.type_().to_opt(), Some(udt_type_script.clone()));
+    }
+    // Metadata lives in the outputs, so no metadata cell dep is needed.
+    assert_eq!(genesis_tx.cell_deps().len(), 2);
+
+    // The owner of the new metadata cell has no input in this transaction.
+    let not_owner_tx = create_and_mint(&user_b);
+    drop(executor);
+
+    let cycles = test_context
+        .context
+        .verify_tx(&genesis_tx, u64::MAX)
+        .expect("Local Create And Mint Tx Failed");
+    println!("Local Create And Mint Tx cycles: {}", cycles);
+
+    let err = test_context
+        .context
+        .verify_tx(&not_owner_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(&udt_type_script, error_code::NO_MINT_PERMISSION)
+            .output_type_script(1),
+        "not owner",
+    );
+}
+
+#[test]
+fn test_local_v1_metadata_and_migration() {
+    let mut test_context = build_test_context();
+    let v1 = SSRIMetadataV1 {
+        name: String::from("Legacy UDT"),
+        symbol: String::from("OLD"),
+        decimals: 6,
+        icon: String::new(),
+    };
+    let type_id_args = [1u8; 32];
+    let v1_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .type_(Some(type_id_script(&type_id_args)).pack())
+            .build(),
+        Bytes::from(serde_molecule::to_vec(&v1, false).unwrap()),
+    );
+    let legacy_udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(type_id_args.to_vec().pack())
+        .build();
+    let invalid_type_id_args = [2u8; 32];
+    test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .type_(Some(type_id_script(&invalid_type_id_args)).pack())
+            .build(),
+        Bytes::from(
+            serde_molecule::to_vec(
+                &SSRIMetadataV1 {
+                    decimals: 40,
+                    ..v1.clone()
+                },
+                false,
+            )
+            .unwrap(),
+        ),
+    );
+    let invalid_udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(invalid_type_id_args.to_vec().pack())
+        .build();
+
+    let migrate_tx = {
+        let executor =
+            SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+        let name = executor
+            .run_script_level_script(&argv::name(), &legacy_udt)
+            .unwrap();
+        assert_eq!(name.content, Bytes::from("Legacy UDT"));
+        let decimals = executor
+            .run_script_level_script(&argv::decimals(), &legacy_udt)
+            .unwrap();
+        assert_eq!(decimals.content, Bytes::from(vec![6]));
+        let description = executor
+            .run_script_level_script(&argv::description(), &legacy_udt)
+            .unwrap();
+        assert!(description.content.is_empty());
+
+        let already_migrated = executor
+            .run_script_level_script(
+                &argv::migrate_metadata(None),
+                &test_context.ssri_udt_type_script,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            already_migrated,
+            ExecutionError::ExitCode(error_code::METADATA_ALREADY_MIGRATED)
+        ));
+
+        let invalid = executor
+            .run_script_level_script(&argv::migrate_metadata(None), &invalid_udt)
+            .unwrap_err();
+        assert!(matches!(
+            invalid,
+            ExecutionError::ExitCode(error_code::INVALID_METADATA_DECIMALS)
+        ));
+
+        let execution = executor
+            .run_script_level_script(&argv::migrate_metadata(None), &legacy_udt)
+            .unwrap();
+        Transaction::from_slice(&execution.content).unwrap()
+    };
+
+    let raw = migrate_tx.raw();
+    assert_eq!(raw.inputs().get(0).unwrap().previous_output(), v1_out_point);
+    assert_eq!(
+        raw.outputs().get(0).unwrap().type_().to_opt(),
+        Some(type_id_script(&type_id_args))
+    );
+    let migrated_data = raw.outputs_data().get(0).unwrap().raw_data();
+    let migrated = SSRIMetadata::decode(&migrated_data).unwrap();
+    // The cell had too little capacity for the new layout.
+    let migrated_cell = raw.outputs().get(0).unwrap();
+    assert_eq!(
+        Capacity::shannons(migrated_cell.capacity().unpack()),
+        migrated_cell
+            .occupied_capacity(Capacity::bytes(migrated_data.len()).unwrap())
+            .unwrap()
+    );
+    assert_eq!(
+        migrated,
+        SSRIMetadata {
+            version: CURRENT_METADATA_VERSION,
+            ..SSRIMetadata::from(v1)
+        }
+    );
+
+    let migrate_tx = migrate_tx
+        .into_view()
+        .as_advanced_builder()
+        .cell_dep(test_context.always_success_dep.clone())
+        .build();
+    let cycles = test_context
+        .context
+        .verify_tx(&migrate_tx, u64::MAX)
+        .expect("Local Migrate Metadata Tx Failed");
+    println!("Local Migrate Metadata Tx cycles: {}", cycles);
+}
+
+#[test]
+fn test_local_immutable_metadata() {
+    let mut test_context = build_test_context();
+    let owner_lock = test_context.admin_lock_script.clone();
+    let metadata = SSRIMetadata {
+        name: String::from("Fixed UDT"),
+        symbol: String::from("FIX"),
+        decimals: 2,
+        ..Default::default()
+    };
+    let owner_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(owner_lock.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let owner_input = CellInput::new_builder()
+        .previous_output(owner_out_point)
+        .build();
+
+    let create_tx = {
+        let executor =
+            SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+        let base_tx = TransactionBuilder::default()
+            .input(owner_input.clone())
+            .build();
+        let execution = executor
+            .run_script_level_code(
+                &argv::create_immutable(&base_tx.data(), &owner_lock, &metadata).unwrap(),
+            )
+            .unwrap();
+        Transaction::from_slice(&execution.content).unwrap()
+    };
+    let metadata_output = create_tx.raw().outputs().get(0).unwrap();
+    assert!(metadata_output.type_().to_opt().is_none());
+    assert_eq!(metadata_output.lock(), owner_lock);
+    let metadata_data = create_tx.raw().outputs_data().get(0).unwrap().raw_data();
+    assert_eq!(SSRIMetadata::decode(&metadata_data).unwrap(), metadata);
+
+    let udt_args = UDTArgs::immutable(owner_lock.calc_script_hash().unpack(), &metadata_data);
+    assert_eq!(UDTArgs::parse(&udt_args.to_vec()), Some(udt_args.clone()));
+    let udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(udt_args.to_vec().pack())
+        .build();
+    let metadata_out_point = test_context
+        .context
+        .create_cell(metadata_output, metadata_data.clone());
+    let metadata_dep = CellDep::new_builder().out_point(metadata_out_point).build();
+
+    // Owner mode comes from the args; the metadata comes from a cell dep with
+    // the committed data.
+    let mint_tx = TransactionBuilder::default()
+        .input(owner_input)
+        .cell_dep(metadata_dep.clone())
+        .output(
+            CellOutput::new_builder()
+                .capacity(100u64.pack())
+                .lock(test_context.normal_user_a_lock_script.clone())
+                .type_(Some(udt.clone()).pack())
+                .build(),
+        )
+        .output_data(Bytes::from(1000u128.to_le_bytes().to_vec()).pack())
+        .cell_dep(test_context.ssri_udt_dep.clone())
+        .cell_dep(test_context.always_success_dep.clone())
+        .build();
+    let cycles = test_context
+        .context
+        .verify_tx(&mint_tx, u64::MAX)
+        .expect("Local Immutable Mint Tx Failed");
+    println!("Local Immutable Mint Tx cycles: {}", cycles);
+
+    let user_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.normal_user_b_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let stranger_mint_tx = mint_tx
+        .as_advanced_builder()
+        .set_inputs(vec![CellInput::new_builder()
+            .previous_output(user_out_point)
+            .build()])
+        .build();
+    let err = test_context
+        .context
+        .verify_tx(&stranger_mint_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(&udt, error_code::NO_MINT_PERMISSION).output_type_script(0),
+        "stranger mint",
+    );
+
+    let no_metadata_mint_tx = mint_tx
+        .as_advanced_builder()
+        .set_cell_deps(vec![
+            test_context.ssri_udt_dep.clone(),
+            test_context.always_success_dep.clone(),
+        ])
+        .build();
+    let err = test_context
+        .context
+        .verify_tx(&no_metadata_mint_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(&udt, error_code::SSRI_CONFIG_NOT_FOUND)
+            .output_type_script(0),
+        "mint without metadata",
+    );
+
+    // Args committing to metadata that breaks its rules cannot mint.
+    let invalid_metadata_data = Bytes::from(
+        SSRIMetadata {
+            decimals: 40,
+            ..metadata.clone()
+        }
+        .encode()
+        .unwrap(),
+    );
+    let invalid_udt = test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(
+            UDTArgs::immutable(
+                owner_lock.calc_script_hash().unpack(),
+                &invalid_metadata_data,
+            )
+            .to_vec()
+            .pack(),
+        )
+        .build();
+    let invalid_metadata_out_point = test_context.context.create_cell(
+        create_tx.raw().outputs().get(0).unwrap(),
+        invalid_metadata_data,
+    );
+    let invalid_mint_tx = mint_tx
+        .as_advanced_builder()
+        .cell_dep(
+            CellDep::new_builder()
+                .out_point(invalid_metadata_out_point)
+                .build(),
+        )
+        .set_outputs(vec![CellOutput::new_builder()
+            .capacity(100u64.pack())
+            .lock(test_context.normal_user_a_lock_script.clone())
+            .type_(Some(invalid_udt.clone()).pack())
+            .build()])
+        .build();
+    let err = test_context
+        .context
+        .verify_tx(&invalid_mint_tx, u64::MAX)
+        .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(&invalid_udt, error_code::INVALID_METADATA_DECIMALS)
+            .output_type_script(0),
+        "invalid metadata",
+    );
+
+    let executor =
+        SSRIExecutor::new(&test_context.context, &test_context.ssri_udt_out_point).unwrap();
+    // `UDT.mint` keeps the committed cell dep of the given transaction and
+    // adds none of its own.
+    let base_tx = TransactionBuilder::default()
+        .cell_dep(metadata_dep.clone())
+        .build()
+        .data();
+    let to_locks = [test_context.normal_user_a_lock_script.clone()];
+    let minted = executor
+        .run_script_level_script(
+            &argv::mint(Some(&base_tx), &to_locks, &[1000]).unwrap(),
+            &udt,
+        )
+        .unwrap();
+    let minted = Transaction::from_slice(&minted.content).unwrap();
+    assert_eq!(
+        minted.raw().cell_deps().as_slice(),
+        base_tx.raw().cell_deps().as_slice()
+    );
+    assert_eq!(minted.raw().outputs().len(), 1);
+
+    // `SSRIUDT.airdrop` chains its batches through the owner lock it finds
+    // among the inputs, and every batch keeps the committed cell dep.
+    let base_tx = TransactionBuilder::default()
+        .input(mint_tx.inputs().get(0).unwrap())
+        .cell_dep(metadata_dep.clone())
+        .build()
+        .data();
+    let to_locks = [
+        test_context.normal_user_a_lock_script.clone(),
+        test_context.normal_user_b_lock_script.clone(),
+    ];
+    let airdropped = executor
+        .run_script_level_script(
+            &argv::airdrop(Some(&base_tx), &to_locks, &[100, 200], 1).unwrap(),
+            &udt,
+        )
+        .unwrap();
+    let airdropped = TransactionVec::from_slice(&airdropped.content).unwrap();
+    assert_eq!(airdropped.len(), 2);
+    let first = airdropped.get(0).unwrap();
+    assert_eq!(first.raw().outputs().get(1).unwrap().lock(), owner_lock);
+    for tx in airdropped.into_iter() {
+        assert_eq!(
+            tx.raw().cell_deps().as_slice(),
+            base_tx.raw().cell_deps().as_slice()
+        );
+    }
+
+    // Any cell dep with the committed data serves the metadata.
+    let name = executor
+        .run(
+            &argv::name(),
+            Level::Transaction {
+                script: udt.clone(),
+                tx: TransactionBuilder::default().cell_dep(metadata_dep).build(),
+            },
+        )
+        .unwrap();
+    assert_eq!(name.content, Bytes::from("Fixed UDT"));
+    let missing = executor
+        .run(
+            &argv::name(),
+            Level::Transaction {
+                script: udt.clone(),
+                tx: TransactionBuilder::default().build(),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(
+        missing,
+        ExecutionError::ExitCode(error_code::SSRI_CONFIG_NOT_FOUND)
+    ));
+    let migrate = executor
+        .run_script_level_script(&argv::migrate_metadata(None), &udt)
+        .unwrap_err();
+    assert!(matches!(
+        migrate,
+        ExecutionError::ExitCode(error_code::IMMUTABLE_METADATA)
+    ));
+}
@@ -0,0 +1,139 @@
+// NOTE: These tests drive the JSON-RPC handler of ssri-server-local directly with the fixture shipped in its crate; run the binary with the same fixture to serve `ssri.rs` on localhost:9090.
+
+use std::path::Path;
+
+use serde_json::{json, Value};
+use ssri_server_local::{
+    handle_body, handle_request, load_fixture, EXECUTION_ERROR, INVALID_PARAMS, METHOD_NOT_FOUND,
+    PARSE_ERROR,
+};
+use ssri_udt_sdk::argv;
+
+const FIXTURE: &str = "../crates/ssri-server-local/fixtures/ssri-udt.json";
+const CODE_TX_HASH: &str = "0x107562d1afa3fad33f08caf222922071165c95811a7f8b394f5914e08e40f8ad";
+
+fn udt_type_script() -> Value {
+    json!({
+        "code_hash": "0x454fcbd37f23c667cedb987ade5e40f5a921e1275508ec0f1998a5ac6f88623c",
+        "hash_type": "data1",
+        "args": format!("0x{}", "00".repeat(32)),
+    })
+}
+
+fn content(response: &Value) -> &str {
+    response["result"]["content"]
+        .as_str()
+        .unwrap_or_else(|| panic!("Unexpected response: {}", response))
+}
+
+#[test]
+fn test_local_server_script_levels() {
+    let context = load_fixture(Path::new(FIXTURE)).unwrap();
+
+    let version = handle_request(
+        &context,
+        &json!({
+            "id": 2,
+            "jsonrpc": "2.0",
+            "method": "run_script_level_code",
+            "params": [CODE_TX_HASH, 0, argv::to_hex(&argv::version())]
+        }),
+    );
+    assert_eq!(version["id"], 2);
+    assert_eq!(content(&version), "0x00");
+
+    let name = handle_request(
+        &context,
+        &json!({
+            "id": 3,
+            "jsonrpc": "2.0",
+            "method": "run_script_level_script",
+            "params": [CODE_TX_HASH, 0, argv::to_hex(&argv::name()), udt_type_script()]
+        }),
+    );
+    assert_eq!(content(&name), format!("0x{}", hex("Test UDT")));
+
+    let decimals = handle_request(
+        &context,
+        &json!({
+            "id": 4,
+            "jsonrpc": "2.0",
+            "method": "run_script_level_cell",
+            "params": [
+                CODE_TX_HASH,
+                0,
+                argv::to_hex(&argv::decimals()),
+                {
+                    "cell_output": {
+                        "capacity": "0x0",
+                        "lock": udt_type_script(),
+                        "type": udt_type_script(),
+                    },
+                    "hex_data": format!("0x{}", "00".repeat(16)),
+                }
+            ]
+        }),
+    );
+    assert_eq!(content(&decimals), "0x08");
+}
+
+#[test]
+fn test_local_server_errors() {
+    let context = load_fixture(Path::new(FIXTURE)).unwrap();
+
+    let not_found = handle_request(
+        &context,
+        &json!({ "id": 1, "jsonrpc": "2.0", "method": "get_tip", "params": [] }),
+    );
+    assert_eq!(not_found["error"]["code"], METHOD_NOT_FOUND);
+
+    let invalid_params = handle_request(
+        &context,
+        &json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "run_script_level_script",
+            "params": [CODE_TX_HASH, 0, argv::to_hex(&argv::name())]
+        }),
+    );
+    assert_eq!(invalid_params["error"]["code"], INVALID_PARAMS);
+
+    // UDT.name needs the script to locate the metadata cell: Error::SSRIConfigNotFound
+    let failed = handle_request(
+        &context,
+        &json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "run_script_level_code",
+            "params": [CODE_TX_HASH, 0, argv::to_hex(&argv::name())]
+        }),
+    );
+    assert_eq!(failed["error"]["code"], EXECUTION_ERROR);
+    println!("Failed response: {}", failed);
+
+    let parse_error = handle_body(&context, b"{");
+    assert_eq!(parse_error["error"]["code"], PARSE_ERROR);
+}
+
+#[test]
+fn test_local_server_toml_fixture() {
+    let path = std::env::temp_dir().join("ssri-server-local-fixture.toml");
+    std::fs::write(
+        &path,
+        r#"
+[[cells]]
+out_point = { tx_hash = "0x2200000000000000000000000000000000000000000000000000000000000000", index = "0x1" }
+output = { capacity = "0x64", lock = { code_hash = "0x0000000000000000000000000000000000000000000000000000000000000000", hash_type = "type", args = "0x" } }
+data = "0x0102"
+"#,
+    )
+    .unwrap();
+    let context = load_fixture(&path).unwrap();
+    assert_eq!(context.cells.len(), 1);
+    let (_, data) = context.cells.values().next().unwrap();
+    assert_eq!(data.as_ref(), &[1, 2]);
+}
+
+fn hex(data: &str) -> String {
+    data.bytes().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -1,8 +1,14 @@
 use ckb_std::ckb_types::prelude::Entity;
 use ckb_std::ckb_types::{bytes::Bytes, packed::*, prelude::*};
-use ckb_testtool::ckb_types::core::TransactionBuilder;
+use ckb_testtool::{
+    ckb_error::Error as CKBError, ckb_script::ScriptError, ckb_types::core::TransactionBuilder,
+};
+use serde_molecule::to_vec;
+use ssri_udt_sdk::{
+    error_code, type_id::type_id_script, MetadataSingleton, SSRIMetadata, SSRIMetadataV1, UDTArgs,
+};
 
-use crate::utils::build_test_context;
+use crate::utils::{assert_script_error, build_test_context, PausableUDTTestContext};
 
 #[test]
 pub fn test_transfer() {
@@ -59,6 +65,60 @@ pub fn test_transfer() {
     println!("Normal Tx cycles: {}", normal_cycles);
 }
 
+#[test]
+pub fn test_transfer_with_malformed_amounts() {
+    let mut test_context = build_test_context();
+    let udt_cell = |lock: &Script| {
+        CellOutput::new_builder()
+            .capacity(100u64.pack())
+            .lock(lock.clone())
+            .type_(Some(test_context.ssri_udt_type_script.clone()).pack())
+            .build()
+    };
+    let user_a = udt_cell(&test_context.normal_user_a_lock_script);
+    let user_b = udt_cell(&test_context.normal_user_b_lock_script);
+
+    let mut verify_transfer = |inputs_data: &[Bytes], output_amount: u128| {
+        let mut tx = TransactionBuilder::default().cell_deps(vec![
+            test_context.ssri_udt_dep.clone(),
+            test_context.always_success_dep.clone(),
+        ]);
+        for data in inputs_data {
+            let out_point = test_context
+                .context
+                .create_cell(user_a.clone(), data.clone());
+            tx = tx.input(CellInput::new_builder().previous_output(out_point).build());
+        }
+        let tx = tx
+            .output(user_b.clone())
+            .output_data(output_amount.pack().as_bytes().pack())
+            .build();
+        test_context.context.verify_tx(&tx, u64::MAX).unwrap_err()
+    };
+    // The group is reported at its first input.
+    let failure = |code| {
+        ScriptError::validation_failure(&test_context.ssri_udt_type_script, code)
+            .input_type_script(0)
+    };
+
+    // Only the 16 amount bytes are loaded, and data of any other length is
+    // rejected.
+    let long = Bytes::from([&1000u128.to_le_bytes()[..], &[0]].concat());
+    let err = verify_transfer(&[long], 1000);
+    assert_script_error(err, failure(error_code::ENCODING), "long amount");
+    let short = Bytes::from(1000u128.to_le_bytes()[..15].to_vec());
+    let err = verify_transfer(&[short], 1000);
+    assert_script_error(err, failure(error_code::ENCODING), "short amount");
+
+    let max = u128::MAX.pack().as_bytes();
+    let err = verify_transfer(&[max.clone(), max], u128::MAX);
+    assert_script_error(
+        err,
+        failure(error_code::AMOUNT_OVERFLOW),
+        "overflowing inputs",
+    );
+}
+
 #[test]
 pub fn test_mint() {
     println!("Entered test_mint");
@@ -134,8 +194,414 @@ pub fn test_mint() {
         .verify_tx(&unauthorized_mint_tx, u64::MAX)
         .unwrap_err();
 
-    println!(
-        "Expected Unauthorized Mint Tx Error: {:?}",
-        unauthorized_mint_err
+    assert_script_error(
+        unauthorized_mint_err,
+        ScriptError::validation_failure(
+            &test_context.ssri_udt_type_script,
+            error_code::NO_MINT_PERMISSION,
+        )
+        .output_type_script(0),
+        "unauthorized mint",
+    );
+}
+
+/// Mints by the admin against a Type ID metadata cell dep holding
+/// `metadata_data`, and returns the verify result.
+fn verify_mint_with_metadata_data(
+    test_context: &mut PausableUDTTestContext,
+    type_id_args: [u8; 32],
+    metadata_data: Bytes,
+) -> Result<u64, CKBError> {
+    let admin_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .build(),
+        Bytes::default(),
+    );
+    let admin_input = CellInput::new_builder()
+        .previous_output(admin_out_point)
+        .build();
+
+    let metadata_out_point = test_context.context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(test_context.admin_lock_script.clone())
+            .type_(Some(type_id_script(&type_id_args)).pack())
+            .build(),
+        metadata_data,
+    );
+    let udt_output = CellOutput::new_builder()
+        .capacity(100u64.pack())
+        .lock(test_context.normal_user_b_lock_script.clone())
+        .type_(
+            Some(udt_type_script(
+                test_context,
+                &UDTArgs::TypeId(type_id_args),
+            ))
+            .pack(),
+        )
+        .build();
+
+    let mint_tx = TransactionBuilder::default()
+        .input(admin_input)
+        .output(udt_output)
+        .output_data(1000u128.pack().as_bytes().pack())
+        .cell_deps(vec![
+            test_context.ssri_udt_dep.clone(),
+            test_context.always_success_dep.clone(),
+            CellDep::new_builder().out_point(metadata_out_point).build(),
+        ])
+        .build();
+    test_context.context.verify_tx(&mint_tx, u64::MAX)
+}
+
+#[test]
+pub fn test_mint_with_invalid_metadata() {
+    let mut test_context = build_test_context();
+    let type_id_args = [0x39; 32];
+    let metadata = SSRIMetadata {
+        name: String::from("Too Precise"),
+        symbol: String::from("PREC"),
+        decimals: 40,
+        icon: String::new(),
+        ..Default::default()
+    };
+
+    let err = verify_mint_with_metadata_data(
+        &mut test_context,
+        type_id_args,
+        Bytes::from(metadata.encode().unwrap()),
+    )
+    .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(
+            &udt_type_script(&test_context, &UDTArgs::TypeId(type_id_args)),
+            error_code::INVALID_METADATA_DECIMALS,
+        )
+        .output_type_script(0),
+        "invalid metadata",
+    );
+}
+
+#[test]
+pub fn test_mint_with_legacy_metadata() {
+    let mut test_context = build_test_context();
+    // Breaks the symbol and icon rules, which came after the v1 layout.
+    let legacy = SSRIMetadataV1 {
+        name: String::from("Old Token"),
+        symbol: String::from("OLD TOKEN"),
+        decimals: 8,
+        icon: String::from("http://example.com/old.png"),
+    };
+
+    // Cells in an older layout mint as they are.
+    verify_mint_with_metadata_data(
+        &mut test_context,
+        [0x3a; 32],
+        Bytes::from(to_vec(&legacy, false).unwrap()),
+    )
+    .expect("mint with legacy metadata");
+
+    // The same metadata in the current layout is held to the rules.
+    let type_id_args = [0x3b; 32];
+    let err = verify_mint_with_metadata_data(
+        &mut test_context,
+        type_id_args,
+        Bytes::from(SSRIMetadata::from(legacy).encode().unwrap()),
+    )
+    .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(
+            &udt_type_script(&test_context, &UDTArgs::TypeId(type_id_args)),
+            error_code::INVALID_METADATA_SYMBOL_CHARACTER,
+        )
+        .output_type_script(0),
+        "current metadata",
+    );
+}
+
+/// The UDT type script of the test context with `udt_args`.
+fn udt_type_script(test_context: &PausableUDTTestContext, udt_args: &UDTArgs) -> Script {
+    test_context
+        .ssri_udt_type_script
+        .clone()
+        .as_builder()
+        .args(udt_args.to_vec().pack())
+        .build()
+}
+
+/// Mints against metadata cells named by `udt_args`, placed as cell deps,
+/// inputs and outputs of the mint, and returns the verify result. `owner`
+/// adds a plain input with that lock. The minted UDT cell follows the
+/// metadata outputs.
+fn verify_mint_with_metadata_cells(
+    test_context: &mut PausableUDTTestContext,
+    udt_args: UDTArgs,
+    owner: Option<&Script>,
+    cell_deps: &[(Script, SSRIMetadata)],
+    inputs: &[(Script, SSRIMetadata)],
+    outputs: &[(Script, SSRIMetadata)],
+) -> Result<u64, CKBError> {
+    let metadata_cell = |(lock, metadata): &(Script, SSRIMetadata)| {
+        let cell = CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(lock.clone())
+            .type_(udt_args.metadata_type_script().pack())
+            .build();
+        let data = Bytes::from(to_vec(metadata, false).unwrap());
+        (cell, data)
+    };
+
+    let mut tx = TransactionBuilder::default().cell_deps(vec![
+        test_context.ssri_udt_dep.clone(),
+        test_context.always_success_dep.clone(),
+    ]);
+    if let Some(owner) = owner {
+        let out_point = test_context.context.create_cell(
+            CellOutput::new_builder()
+                .capacity(10000u64.pack())
+                .lock(owner.clone())
+                .build(),
+            Bytes::default(),
+        );
+        tx = tx.input(CellInput::new_builder().previous_output(out_point).build());
+    }
+    for entry in cell_deps {
+        let (cell, data) = metadata_cell(entry);
+        let out_point = test_context.context.create_cell(cell, data);
+        tx = tx.cell_dep(CellDep::new_builder().out_point(out_point).build());
+    }
+    for entry in inputs {
+        let (cell, data) = metadata_cell(entry);
+        let out_point = test_context.context.create_cell(cell, data);
+        tx = tx.input(CellInput::new_builder().previous_output(out_point).build());
+    }
+    for entry in outputs {
+        let (cell, data) = metadata_cell(entry);
+        tx = tx.output(cell).output_data(data.pack());
+    }
+
+    let udt_type_script = udt_type_script(test_context, &udt_args);
+    let tx = tx
+        .output(
+            CellOutput::new_builder()
+                .capacity(100u64.pack())
+                .lock(test_context.normal_user_b_lock_script.clone())
+                .type_(Some(udt_type_script).pack())
+                .build(),
+        )
+        .output_data(1000u128.pack().as_bytes().pack())
+        .build();
+
+    test_context.context.verify_tx(&tx, u64::MAX)
+}
+
+#[test]
+pub fn test_mint_metadata_cell_precedence() {
+    let mut test_context = build_test_context();
+    let admin = test_context.admin_lock_script.clone();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let metadata = SSRIMetadata {
+        name: String::from("Precedence"),
+        symbol: String::from("PREC"),
+        decimals: 8,
+        icon: String::new(),
+        ..Default::default()
+    };
+    let renamed = SSRIMetadata {
+        name: String::from("Renamed"),
+        ..metadata.clone()
+    };
+    let invalid = SSRIMetadata {
+        decimals: 40,
+        ..metadata.clone()
+    };
+
+    // Updating the metadata while minting: the spent cell proves ownership.
+    verify_mint_with_metadata_cells(
+        &mut test_context,
+        UDTArgs::TypeId([0x60; 32]),
+        None,
+        &[],
+        &[(admin.clone(), metadata.clone())],
+        &[(admin.clone(), renamed.clone())],
+    )
+    .expect("mint while updating metadata");
+
+    // Destroying the metadata while minting still finds it in the inputs.
+    verify_mint_with_metadata_cells(
+        &mut test_context,
+        UDTArgs::TypeId([0x61; 32]),
+        None,
+        &[],
+        &[(admin.clone(), metadata.clone())],
+        &[],
+    )
+    .expect("mint while destroying metadata");
+
+    // Handing the metadata to another lock: the lock before the
+    // transaction is the owner, so the spent admin cell authorizes it.
+    verify_mint_with_metadata_cells(
+        &mut test_context,
+        UDTArgs::TypeId([0x62; 32]),
+        None,
+        &[],
+        &[(admin.clone(), metadata.clone())],
+        &[(user_a.clone(), metadata.clone())],
+    )
+    .expect("mint while transferring metadata");
+
+    // The same cell dep listed twice is one cell.
+    let err = verify_mint_with_metadata_cells(
+        &mut test_context,
+        UDTArgs::TypeId([0x63; 32]),
+        None,
+        &[
+            (admin.clone(), metadata.clone()),
+            (admin.clone(), metadata.clone()),
+        ],
+        &[],
+        &[],
+    )
+    .unwrap_err();
+    // Nothing in the inputs is locked by admin.
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(
+            &udt_type_script(&test_context, &UDTArgs::TypeId([0x63; 32])),
+            error_code::NO_MINT_PERMISSION,
+        )
+        .output_type_script(0),
+        "no mint permission",
+    );
+
+    // Two cell deps that disagree on the metadata.
+    let err = verify_mint_with_metadata_cells(
+        &mut test_context,
+        UDTArgs::TypeId([0x64; 32]),
+        None,
+        &[
+            (admin.clone(), metadata.clone()),
+            (user_a.clone(), metadata.clone()),
+        ],
+        &[],
+        &[],
+    )
+    .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(
+            &udt_type_script(&test_context, &UDTArgs::TypeId([0x64; 32])),
+            error_code::CONFLICTING_SSRI_CONFIG,
+        )
+        .output_type_script(0),
+        "conflicting metadata",
+    );
+
+    // A cell dep and an input that disagree on the metadata.
+    let err = verify_mint_with_metadata_cells(
+        &mut test_context,
+        UDTArgs::TypeId([0x65; 32]),
+        None,
+        &[(user_a.clone(), metadata.clone())],
+        &[(admin.clone(), renamed.clone())],
+        &[],
+    )
+    .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(
+            &udt_type_script(&test_context, &UDTArgs::TypeId([0x65; 32])),
+            error_code::CONFLICTING_SSRI_CONFIG,
+        )
+        .output_type_script(0),
+        "conflicting metadata",
+    );
+
+    // The metadata after the transaction is what gets validated.
+    let err = verify_mint_with_metadata_cells(
+        &mut test_context,
+        UDTArgs::TypeId([0x66; 32]),
+        None,
+        &[],
+        &[(admin.clone(), metadata.clone())],
+        &[(admin.clone(), invalid)],
+    )
+    .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(
+            &udt_type_script(&test_context, &UDTArgs::TypeId([0x66; 32])),
+            error_code::INVALID_METADATA_DECIMALS,
+        )
+        .output_type_script(1),
+        "invalid metadata",
+    );
+}
+
+#[test]
+pub fn test_mint_with_singleton_metadata() {
+    let mut test_context = build_test_context();
+    let admin = test_context.admin_lock_script.clone();
+    let user_a = test_context.normal_user_a_lock_script.clone();
+    let metadata = SSRIMetadata {
+        name: String::from("Unique"),
+        symbol: String::from("UNQ"),
+        decimals: 8,
+        icon: String::new(),
+        ..Default::default()
+    };
+    let unique_args = UDTArgs::singleton(MetadataSingleton::UniqueCell, &[0x70; 20]);
+
+    // Owner mode is an input with the lock of the metadata cell.
+    let err = verify_mint_with_metadata_cells(
+        &mut test_context,
+        unique_args.clone(),
+        Some(&admin),
+        &[(user_a.clone(), metadata.clone())],
+        &[],
+        &[],
+    )
+    .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(
+            &udt_type_script(&test_context, &unique_args),
+            error_code::NO_MINT_PERMISSION,
+        )
+        .output_type_script(0),
+        "no mint permission",
+    );
+    verify_mint_with_metadata_cells(
+        &mut test_context,
+        unique_args.clone(),
+        Some(&admin),
+        &[(admin.clone(), metadata.clone())],
+        &[],
+        &[],
+    )
+    .expect("mint with unique cell metadata");
+
+    // Copies that differ are rejected whichever script guards them.
+    let err = verify_mint_with_metadata_cells(
+        &mut test_context,
+        unique_args.clone(),
+        Some(&admin),
+        &[(admin.clone(), metadata.clone()), (user_a, metadata)],
+        &[],
+        &[],
+    )
+    .unwrap_err();
+    assert_script_error(
+        err,
+        ScriptError::validation_failure(
+            &udt_type_script(&test_context, &unique_args),
+            error_code::CONFLICTING_SSRI_CONFIG,
+        )
+        .output_type_script(0),
+        "conflicting metadata",
     );
 }
@@ -1,30 +1,33 @@
-use ckb_hash::blake2b_256;
 use ckb_std::{
     ckb_types::{bytes::Bytes, core::ScriptHashType, packed::*, prelude::*},
     high_level::encode_hex,
 };
 use ckb_testtool::{
-    builtin::ALWAYS_SUCCESS, ckb_chain_spec::consensus::TYPE_ID_CODE_HASH, context::Context,
+    builtin::ALWAYS_SUCCESS, ckb_chain_spec::consensus::TYPE_ID_CODE_HASH,
+    ckb_error::Error as CKBError, ckb_script::TransactionScriptError, context::Context,
 };
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
 use serde_molecule::to_vec;
+use ssri_udt_sdk::{
+    argv::{self, method_path},
+    SSRIMetadata,
+};
 
 use crate::Loader;
 
-pub fn method_path(name: impl AsRef<[u8]>) -> u64 {
-    u64::from_le_bytes(blake2b_256(name)[0..8].try_into().unwrap())
+pub fn method_path_hex(name: &str) -> String {
+    argv::to_hex(&[method_path(name).to_le_bytes().to_vec()]).remove(0)
 }
 
-pub fn method_path_hex(name: impl AsRef<[u8]>) -> String {
-    let method_path = method_path(name);
-    let method_path_in_bytes = method_path.to_le_bytes();
-    let method_path_hex = format!(
-        "0x{:?}",
-        encode_hex(&method_path_in_bytes).into_string().unwrap()
-    )
-    .replace("\"", "");
-    method_path_hex
+/// Asserts that verification failed with exactly `expected`: the same exit
+/// code, from the same script, reported for the same script group.
+pub fn assert_script_error(err: CKBError, expected: TransactionScriptError, case: &str) {
+    assert_eq!(
+        err.to_string(),
+        CKBError::from(expected).to_string(),
+        "{}",
+        case
+    );
 }
 
 pub async fn get_ssri_response(payload: serde_json::Value) -> serde_json::Value {
@@ -49,19 +52,13 @@ pub async fn get_ssri_response(payload: serde_json::Value) -> serde_json::Value
     response_json
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct SSRIMetadata {
-    pub name: String,
-    pub symbol: String,
-    pub decimals: u8,
-    pub icon: String,
-}
-
 pub struct PausableUDTTestContext {
     pub context: Context,
     pub always_success_dep: CellDep,
+    pub ssri_udt_out_point: OutPoint,
     pub ssri_udt_dep: CellDep,
     pub ssri_udt_type_script: Script,
+    pub ssri_metadata_out_point: OutPoint,
     pub ssri_metadata_dep: CellDep,
     pub admin_lock_script: Script,
     pub normal_user_a_lock_script: Script,
@@ -125,6 +122,7 @@ pub fn build_test_context() -> PausableUDTTestContext {
         symbol: String::from("TEST"),
         decimals: 8,
         icon: String::from("https://example.com/icon.png"),
+        ..Default::default()
     };
     let ssri_metadata_args = vec![0; 32];
     let ssri_metadata_type_script = Script::new_builder()
@@ -159,8 +157,10 @@ pub fn build_test_context() -> PausableUDTTestContext {
     PausableUDTTestContext {
         context,
         always_success_dep,
+        ssri_udt_out_point,
         ssri_udt_dep,
         ssri_udt_type_script,
+        ssri_metadata_out_point,
         ssri_metadata_dep,
         admin_lock_script,
         normal_user_a_lock_script,